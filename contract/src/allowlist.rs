@@ -0,0 +1,118 @@
+use crate::*;
+
+/// merkle root of the accounts allowed to mint before the series opens to the public
+/// leaves are sha256("account_id") or sha256("account_id:allocation")
+/// pairs are hashed in sorted order, sha256(min(a, b) + max(a, b))
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Allowlist {
+	root: CryptoHash,
+	/// block timestamp (nanoseconds) the drop opens to the public, None keeps it allowlist only
+	ends_at: Option<u64>,
+}
+
+#[near_bindgen]
+impl Contract {
+	/// series owner sets, replaces or clears (None) the allowlist root
+	#[payable]
+	pub fn nft_set_series_allowlist(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+		root: Option<Base64VecU8>,
+		ends_at: Option<U64>,
+	) {
+		assert!(env::attached_deposit() > 0, "Requires attached deposit of at least 1 yoctoNEAR");
+		let initial_storage_usage = env::storage_usage();
+
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		assert_eq!(&env::predecessor_account_id(), &token_series.owner_id, "not series owner");
+
+		token_series.allowlist = root.map(|root| {
+			assert_eq!(root.0.len(), 32, "root has to be 32 bytes");
+			let mut hash = CryptoHash::default();
+			hash.copy_from_slice(&root.0);
+			Allowlist {
+				root: hash,
+				ends_at: ends_at.map(|v| v.0),
+			}
+		});
+		self.token_series_by_id.insert(&token_series_id, &token_series);
+
+		self.refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+	}
+
+	/// allowlisted accounts mint with a proof of their leaf, paying the public mint NEAR price plus storage
+	#[payable]
+	pub fn nft_allowlist_mint(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+		allocation: Option<u32>,
+		proof: Vec<Base64VecU8>,
+	) -> Token {
+		let initial_storage_usage = env::storage_usage();
+
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		let receiver_id = env::predecessor_account_id();
+
+		let allowlist = token_series.allowlist.as_ref().expect("no allowlist");
+		if let Some(ends_at) = allowlist.ends_at {
+			assert!(env::block_timestamp() < ends_at, "allowlist ended");
+		}
+		assert!(
+			verify_allowlist_proof(&allowlist.root, &receiver_id, allocation, &proof),
+			"invalid allowlist proof"
+		);
+		if let Some(allocation) = allocation {
+			assert!(
+				token_series.mints_per_account.get(&receiver_id).unwrap_or(0) < allocation,
+				"allowlist allocation used"
+			);
+		}
+		let price = public_mint_price(&token_series).expect("not for sale in NEAR");
+
+		let token = self.internal_mint_series(token_series_id, &mut token_series, receiver_id.clone(), &receiver_id, None);
+
+		self.refund_deposit_with_price(env::storage_usage() - initial_storage_usage, price);
+		pay_primary_sale(&token_series, price);
+
+		token
+	}
+
+	/// views
+
+	/// block timestamp the series opens to the public, None if there's no allowlist
+	pub fn nft_series_allowlist_ends_at(&self, token_series_title: TokenSeriesTitle) -> Option<U64> {
+		let token_series = self.token_series_by_id.get(&self.token_series_by_title.get(&token_series_title).expect("no series")).expect("no series");
+		token_series.allowlist.as_ref().and_then(|allowlist| allowlist.ends_at).map(U64)
+	}
+}
+
+/// public mints and market lazy mints wait for the allowlist phase to end
+pub(crate) fn assert_public_phase(token_series: &TokenSeries) {
+	if let Some(allowlist) = token_series.allowlist.as_ref() {
+		let ends_at = allowlist.ends_at.expect("allowlist only");
+		assert!(env::block_timestamp() >= ends_at, "allowlist only until {}", ends_at);
+	}
+}
+
+pub(crate) fn verify_allowlist_proof(
+	root: &CryptoHash,
+	account_id: &AccountId,
+	allocation: Option<u32>,
+	proof: &[Base64VecU8],
+) -> bool {
+	let leaf = match allocation {
+		Some(allocation) => format!("{}{}{}", account_id, TOKEN_DELIMETER, allocation),
+		None => account_id.to_string(),
+	};
+	let mut hash = env::sha256(leaf.as_bytes());
+	for sibling in proof {
+		hash = if hash <= sibling.0 {
+			env::sha256(&[hash, sibling.0.clone()].concat())
+		} else {
+			env::sha256(&[sibling.0.clone(), hash].concat())
+		};
+	}
+	hash.as_slice() == root
+}
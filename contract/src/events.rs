@@ -0,0 +1,192 @@
+use crate::*;
+use near_sdk::json_types::Base58CryptoHash;
+
+/// 1.1.0 adds contract_metadata_update
+const NEP171_VERSION: &str = "1.1.0";
+const NFT_SERIES_VERSION: &str = "1.0.0";
+
+/// NEP-297 event, logged as EVENT_JSON:{"standard":..,"version":..,"event":..,"data":[..]}
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "standard")]
+#[serde(rename_all = "snake_case")]
+enum Event<'a> {
+	Nep171(Nep171Event<'a>),
+	NftSeries(NftSeriesEvent<'a>),
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct Nep171Event<'a> {
+	version: &'static str,
+	#[serde(flatten)]
+	event_kind: Nep171EventKind<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct NftSeriesEvent<'a> {
+	version: &'static str,
+	#[serde(flatten)]
+	event_kind: NftSeriesEventKind<'a>,
+}
+
+/// every event goes through here
+fn emit_event(event: Event) {
+	env::log_str(&format!("{}{}", EVENT_JSON, near_sdk::serde_json::to_string(&event).unwrap()));
+}
+
+/// NEP-171 events
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+pub enum Nep171EventKind<'a> {
+	NftMint(&'a [NftMintData<'a>]),
+	NftTransfer(&'a [NftTransferData<'a>]),
+	NftBurn(&'a [NftBurnData<'a>]),
+	ContractMetadataUpdate(&'a [ContractMetadataUpdateData<'a>]),
+}
+
+impl Nep171EventKind<'_> {
+	pub fn emit(self) {
+		emit_event(Event::Nep171(Nep171Event { version: NEP171_VERSION, event_kind: self }))
+	}
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftMintData<'a> {
+	pub owner_id: &'a AccountId,
+	pub token_ids: Vec<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub memo: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftTransferData<'a> {
+	/// approved account that moved the token, None when the owner did
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub authorized_id: Option<&'a AccountId>,
+	pub old_owner_id: &'a AccountId,
+	pub new_owner_id: &'a AccountId,
+	pub token_ids: Vec<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub memo: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftBurnData<'a> {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub authorized_id: Option<&'a AccountId>,
+	pub owner_id: &'a AccountId,
+	pub token_ids: Vec<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub memo: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractMetadataUpdateData<'a> {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub memo: Option<&'a str>,
+}
+
+/// nft_series events
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+pub enum NftSeriesEventKind<'a> {
+	SeriesCreate(&'a [SeriesCreateData<'a>]),
+	SeriesUpdate(&'a [SeriesUpdateData<'a>]),
+	SeriesDelete(&'a [SeriesDeleteData<'a>]),
+	/// new_owner_id is pending until series_transfer
+	SeriesTransferPropose(&'a [SeriesTransferData<'a>]),
+	SeriesTransfer(&'a [SeriesTransferData<'a>]),
+	SeriesMinterGrant(&'a [SeriesMinterGrantData<'a>]),
+	SeriesMinterRevoke(&'a [SeriesMinterRevokeData<'a>]),
+	ContractPause(&'a [ContractPauseData<'a>]),
+	ContractUnpause(&'a [ContractPauseData<'a>]),
+	UpgradeStage(&'a [UpgradeStageData]),
+	UpgradeDeploy(&'a [UpgradeDeployData]),
+}
+
+impl NftSeriesEventKind<'_> {
+	pub fn emit(self) {
+		emit_event(Event::NftSeries(NftSeriesEvent { version: NFT_SERIES_VERSION, event_kind: self }))
+	}
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesCreateData<'a> {
+	pub token_series_id: TokenSeriesId,
+	pub token_series_title: &'a str,
+	pub owner_id: &'a AccountId,
+	pub transferable: bool,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesUpdateData<'a> {
+	pub token_series_id: TokenSeriesId,
+	pub token_series_title: &'a str,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub updated_at: Option<&'a str>,
+	pub metadata_frozen: bool,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesDeleteData<'a> {
+	pub token_series_id: TokenSeriesId,
+	pub token_series_title: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesTransferData<'a> {
+	pub token_series_id: TokenSeriesId,
+	pub old_owner_id: &'a AccountId,
+	pub new_owner_id: &'a AccountId,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesMinterGrantData<'a> {
+	pub token_series_id: TokenSeriesId,
+	pub account_id: &'a AccountId,
+	pub quota: Option<u32>,
+	pub expires_at: Option<U64>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesMinterRevokeData<'a> {
+	pub token_series_id: TokenSeriesId,
+	pub account_id: &'a AccountId,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractPauseData<'a> {
+	pub feature: &'a PauseFeature,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UpgradeStageData {
+	pub code_hash: Base58CryptoHash,
+	pub deployable_at: U64,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UpgradeDeployData {
+	pub code_hash: Base58CryptoHash,
+}
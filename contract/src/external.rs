@@ -0,0 +1,45 @@
+use crate::*;
+
+/// external contract calls
+
+#[ext_contract(ext_series_approval_receiver)]
+trait SeriesApprovalReceiver {
+	fn series_on_approve(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+		owner_id: AccountId,
+		approval_id: u64,
+		msg: String,
+	);
+}
+
+#[ext_contract(ext_nft_approval_receiver)]
+trait NonFungibleTokenApprovalReceiver {
+	fn nft_on_approve(
+		&mut self,
+		token_id: TokenId,
+		owner_id: AccountId,
+		approval_id: u64,
+		msg: String,
+	);
+}
+
+#[ext_contract(ext_self)]
+trait SelfContract {
+	fn ft_resolve_payout(
+		&mut self,
+		ft_token_id: AccountId,
+		receiver_id: AccountId,
+		amount: U128,
+	);
+}
+
+#[ext_contract(ext_ft)]
+trait FungibleToken {
+	fn ft_transfer(
+		&mut self,
+		receiver_id: AccountId,
+		amount: U128,
+		memo: Option<String>
+	);
+}
@@ -0,0 +1,218 @@
+use crate::*;
+
+impl Contract {
+	pub(crate) fn assert_valid_royalty(&self, royalty: &HashMap<AccountId, u32>) {
+		self.assert_valid_shares("royalty", royalty, self.royalty_limits.max_total);
+	}
+
+	/// primary sale split may give away all of the proceeds
+	pub(crate) fn assert_valid_split(&self, split: &HashMap<AccountId, u32>) {
+		self.assert_valid_shares("split", split, ROYALTY_BASIS_POINTS);
+	}
+
+	fn assert_valid_shares(&self, name: &str, shares: &HashMap<AccountId, u32>, max_total: u32) {
+		assert!(
+			shares.len() as u32 <= self.royalty_limits.max_receivers,
+			"{} has {} receivers, max is {}",
+			name,
+			shares.len(),
+			self.royalty_limits.max_receivers
+		);
+		let mut total: u64 = 0;
+		for (account_id, amount) in shares.iter() {
+			assert!(*amount > 0, "{} for {} is 0", name, account_id);
+			total += *amount as u64;
+		}
+		assert!(
+			total <= max_total as u64,
+			"{} total {} exceeds max {} basis points",
+			name,
+			total,
+			max_total
+		);
+	}
+
+	pub(crate) fn assert_transferable(&self, token_id: &TokenId) {
+		let token_series_id = token_series_id_from_token_id(token_id);
+		let token_series = self.token_series_by_id.get(&token_series_id).expect("no series");
+		assert!(token_series.transferable, "series is not transferable");
+	}
+
+	pub(crate) fn internal_add_series_to_owner(
+		&mut self,
+		owner_id: &AccountId,
+		token_series_id: TokenSeriesId,
+	) {
+		let mut series_set = self.series_by_owner.get(owner_id).unwrap_or_else(|| {
+			UnorderedSet::new(StorageKey::SeriesByOwnerInner {
+				account_id_hash: hash_account_id(owner_id),
+			})
+		});
+		series_set.insert(&token_series_id);
+		self.series_by_owner.insert(owner_id, &series_set);
+	}
+
+	pub(crate) fn internal_remove_series_from_owner(
+		&mut self,
+		owner_id: &AccountId,
+		token_series_id: TokenSeriesId,
+	) {
+		let mut series_set = if let Some(series_set) = self.series_by_owner.get(owner_id) {
+			series_set
+		} else {
+			return;
+		};
+		series_set.remove(&token_series_id);
+		if series_set.is_empty() {
+			self.series_by_owner.remove(owner_id);
+		} else {
+			self.series_by_owner.insert(owner_id, &series_set);
+		}
+	}
+
+	/// royalty payouts for token_id sold at balance, shared by nft_payout and nft_transfer_payout
+	pub(crate) fn internal_payout(
+		&self,
+		token_id: &TokenId,
+		owner_id: &AccountId,
+		balance: U128,
+		max_len_payout: Option<u32>,
+	) -> Payout {
+		let token_series_id = token_series_id_from_token_id(token_id);
+		let royalty = self.token_series_by_id.get(&token_series_id).expect("no series").into_inner().royalty;
+
+		let payout = royalty_payout(&royalty, owner_id, balance.into());
+		// the seller is one of the receivers
+		if let Some(max_len_payout) = max_len_payout {
+			assert!(payout.payout.len() as u32 <= max_len_payout, "exceeds max_len_payout");
+		}
+		payout
+	}
+
+	/// mints the next edition of a series to receiver_id, storage_payer_id gets the storage back on nft_burn
+	/// callers are responsible for authorization and storage
+	pub(crate) fn internal_mint_series(
+		&mut self,
+		token_series_id: TokenSeriesId,
+		token_series: &mut VersionedTokenSeries,
+		receiver_id: AccountId,
+		storage_payer_id: &AccountId,
+		edition_metadata: Option<EditionMetadata>,
+	) -> Token {
+		let token = self.internal_mint_edition(token_series_id, token_series, receiver_id, storage_payer_id, edition_metadata);
+		self.token_series_by_id.insert(&token_series_id, token_series);
+
+		log_nft_mint(&token.owner_id, &[token.token_id.clone()]);
+
+		token
+	}
+
+	/// mints without saving token_series or logging, see internal_mint_series
+	pub(crate) fn internal_mint_edition(
+		&mut self,
+		token_series_id: TokenSeriesId,
+		token_series: &mut TokenSeries,
+		receiver_id: AccountId,
+		storage_payer_id: &AccountId,
+		edition_metadata: Option<EditionMetadata>,
+	) -> Token {
+		self.assert_not_paused(PauseFeature::Mint);
+		assert_mint_window(token_series);
+		let max_copies = token_series.metadata.copies.unwrap_or(u64::MAX);
+		assert_ne!(token_series.num_minted, max_copies, "series supply maxed");
+
+		// only counted when something reads the count, so unlimited series don't pay for it
+		if token_series.max_per_account.is_some() || token_series.allowlist.is_some() {
+			let num_mints = token_series.mints_per_account.get(&receiver_id).unwrap_or(0) + 1;
+			if let Some(max_per_account) = token_series.max_per_account {
+				assert!(num_mints <= max_per_account, "{} reached max_per_account", receiver_id);
+			}
+			token_series.mints_per_account.insert(&receiver_id, &num_mints);
+		}
+
+		token_series.num_minted += 1;
+		let token_id = format!("{}{}{}", &token_series_id, TOKEN_DELIMETER, token_series.num_minted);
+		token_series.tokens.insert(&token_id);
+		self.storage_payer_by_id.insert(&token_id, storage_payer_id);
+
+		// per token metadata, self.nft_token "patches" the non-empty fields over the series metadata
+		let EditionMetadata { media, media_hash, extra, issued_at, reference, reference_hash } = edition_metadata.unwrap_or_default();
+		let metadata = Some(TokenMetadata {
+			title: None, // ex. "Arch Nemesis: Mail Carrier" or "Parcel #5055"
+			description: None, // free-form description
+			media, // URL to associated media, preferably to decentralized, content-addressed storage
+			copies: None, // number of copies of this set of metadata in existence when token was minted.
+			media_hash, // Base64-encoded sha256 hash of content referenced by the `media` field. Required if `media` is included.
+			issued_at: Some(issued_at.unwrap_or_else(|| (env::block_timestamp() / 1_000_000).to_string())), // datetime when token was issued or minted
+			expires_at: None, // ISO 8601 datetime when token expires
+			starts_at: None, // ISO 8601 datetime when token starts being valid
+			updated_at: None, // ISO 8601 datetime when token was last updated
+			extra, // anything extra the NFT wants to store on-chain. Can be stringified JSON.
+			reference, // URL to an off-chain JSON file with more info.
+			reference_hash, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
+		});
+		// no refund here, storage is paid once by the caller through refund_deposit or the storage balance
+		self.tokens.internal_mint_with_refund(token_id, receiver_id, metadata, None)
+	}
+	/// removes token_id, storage goes back to whoever paid for it, callers check the owner
+	pub(crate) fn internal_burn(&mut self, owner_id: &AccountId, token_id: &TokenId) {
+		// approvals were paid for by the owner
+		let initial_storage_usage = env::storage_usage();
+		if let Some(approvals_by_id) = self.tokens.approvals_by_id.as_mut() {
+			approvals_by_id.remove(token_id);
+		}
+		if let Some(next_approval_id_by_id) = self.tokens.next_approval_id_by_id.as_mut() {
+			next_approval_id_by_id.remove(token_id);
+		}
+		self.refund_storage(owner_id, initial_storage_usage.saturating_sub(env::storage_usage()));
+
+		let initial_storage_usage = env::storage_usage();
+		self.tokens.owner_by_id.remove(token_id);
+		if let Some(tokens_per_owner) = self.tokens.tokens_per_owner.as_mut() {
+			let mut token_ids = tokens_per_owner.get(owner_id).expect("no tokens for owner");
+			token_ids.remove(token_id);
+			if token_ids.is_empty() {
+				tokens_per_owner.remove(owner_id);
+			} else {
+				tokens_per_owner.insert(owner_id, &token_ids);
+			}
+		}
+		if let Some(token_metadata_by_id) = self.tokens.token_metadata_by_id.as_mut() {
+			token_metadata_by_id.remove(token_id);
+		}
+		let token_series_id = token_series_id_from_token_id(token_id);
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no series");
+		token_series.tokens.remove(token_id);
+		self.token_series_by_id.insert(&token_series_id, &token_series);
+		let storage_payer_id = self.storage_payer_by_id.remove(token_id).unwrap_or_else(|| owner_id.clone());
+		self.refund_storage(&storage_payer_id, initial_storage_usage.saturating_sub(env::storage_usage()));
+
+		Nep171EventKind::NftBurn(&[NftBurnData {
+			authorized_id: None,
+			owner_id,
+			token_ids: vec![token_id],
+			memo: None,
+		}]).emit();
+	}
+
+	/// removes a series with nothing minted, storage goes back to owner_id
+	pub(crate) fn internal_delete_series(
+		&mut self,
+		owner_id: &AccountId,
+		token_series_id: TokenSeriesId,
+		token_series_title: &TokenSeriesTitle,
+	) {
+		let initial_storage_usage = env::storage_usage();
+
+		self.token_series_by_title.remove(token_series_title);
+		self.token_series_by_id.remove(&token_series_id);
+		self.internal_remove_series_from_owner(owner_id, token_series_id);
+
+		self.refund_storage(owner_id, initial_storage_usage.saturating_sub(env::storage_usage()));
+
+		NftSeriesEventKind::SeriesDelete(&[SeriesDeleteData {
+			token_series_id,
+			token_series_title,
+		}]).emit();
+	}
+}
@@ -1,4 +1,21 @@
+mod allowlist;
+mod events;
+mod external;
+mod internal;
+mod migrate;
+mod pause;
+mod public_mint;
+mod roles;
+mod storage;
+mod upgrade;
 mod utils;
+use crate::allowlist::*;
+use crate::events::*;
+use crate::external::*;
+use crate::migrate::*;
+use crate::pause::*;
+use crate::public_mint::*;
+use crate::upgrade::*;
 use crate::utils::*;
 
 use std::collections::HashMap;
@@ -9,17 +26,35 @@ use near_contract_standards::non_fungible_token::{Token, TokenId};
 use near_contract_standards::non_fungible_token::core::{
 	NonFungibleTokenCore, NonFungibleTokenResolver
 };
+use near_contract_standards::non_fungible_token::approval::NonFungibleTokenApproval;
 use near_contract_standards::non_fungible_token::NonFungibleToken;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LazyOption, Vector, LookupMap, UnorderedMap, UnorderedSet};
-use near_sdk::json_types::{U64, U128};
+use near_sdk::json_types::{Base64VecU8, U64, U128};
 use near_sdk::{
-    env, near_bindgen, serde_json::json, Balance, AccountId, BorshStorageKey, PanicOnDefault, Promise, PromiseOrValue,
+    assert_one_yocto, env, ext_contract, near_bindgen, Balance, AccountId, BorshStorageKey, CryptoHash, Gas, PanicOnDefault, Promise, PromiseOrValue,
 };
 use near_sdk::serde::{Deserialize, Serialize};
 
 /// CUSTOM TYPES
 
+const NO_DEPOSIT: Balance = 0;
+/// gas kept back from the prepaid gas when calling series_on_approve on the market
+const GAS_FOR_SERIES_APPROVE: Gas = Gas(10_000_000_000_000);
+/// gas kept back from the prepaid gas when calling nft_on_approve
+const GAS_FOR_NFT_APPROVE: Gas = Gas(10_000_000_000_000);
+
+/// nft_batch_mint_series stops minting when less than this is left
+const GAS_FOR_BATCH_MINT_EDITION: Gas = Gas(10_000_000_000_000);
+
+/// royalties are in basis points of the sale balance
+pub const ROYALTY_BASIS_POINTS: u32 = 10_000;
+/// market pays out at most 10 receivers per sale, the seller and any refunded bids included
+const MAX_ROYALTY_MAX_RECEIVERS: u32 = 9;
+/// 6 royalties + the seller leave room for 3 bid refunds in the same resolve_purchase
+const DEFAULT_ROYALTY_MAX_RECEIVERS: u32 = 6;
+const DEFAULT_ROYALTY_MAX_TOTAL: u32 = 5_000;
+
 /// log series const
 pub const EVENT_JSON: &str = "EVENT_JSON:";
 /// between token_series_id and edition number e.g. 42:2 where 42 is series and 2 is edition
@@ -36,20 +71,84 @@ pub struct TokenSeries {
 	owner_id: AccountId,
 	royalty: HashMap<AccountId, u32>,
 	tokens: UnorderedSet<TokenId>,
+	/// editions ever minted, burned tokens are not reused so this is the last edition number
+	num_minted: u64,
 	approved_market_id: Option<AccountId>,
+	/// incremented every time a market is approved for the series
+	approval_id: u64,
+	/// one-way switch, once true nft_update_series_metadata is rejected
+	metadata_frozen: bool,
+	/// proposed by nft_transfer_series_ownership, becomes owner_id on nft_accept_series_ownership
+	pending_owner_id: Option<AccountId>,
+	/// accounts other than owner_id allowed to mint
+	minters: HashMap<AccountId, MinterGrant>,
+	/// prices for nft_public_mint, None is not for public sale
+	public_mint: Option<PublicMint>,
+	/// block timestamps (nanoseconds) bounding every mint path, supply is capped once ended
+	mint_starts_at: Option<u64>,
+	mint_ends_at: Option<u64>,
+	/// editions any one receiver can get from the series, None is unlimited
+	max_per_account: Option<u32>,
+	/// editions minted to each receiver while max_per_account or an allowlist is set
+	/// (burns and transfers are not subtracted)
+	mints_per_account: LookupMap<AccountId, u32>,
+	/// while set (and not ended) only allowlisted accounts can buy
+	allowlist: Option<Allowlist>,
+	/// false makes the series soulbound, its tokens can be minted and burned but never transferred or approved
+	transferable: bool,
 }
 #[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct TokenSeriesJson {
+	token_series_id: U64,
 	metadata: TokenMetadata,
 	owner_id: AccountId,
 	royalty: HashMap<AccountId, u32>,
+	metadata_frozen: bool,
+	mint_starts_at: Option<U64>,
+	mint_ends_at: Option<U64>,
+	transferable: bool,
+}
+/// minting rights granted by the series owner
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MinterGrant {
+	/// editions left to mint, None is unlimited
+	quota: Option<u32>,
+	/// block timestamp (nanoseconds) after which the grant no longer applies
+	expires_at: Option<U64>,
 }
+/// fields of the series metadata the owner can update, None leaves the field unchanged
 #[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
-pub struct SeriesMintArgs {
-	token_series_title: TokenSeriesTitle,
-	receiver_id: AccountId,
+pub struct SeriesMetadataUpdate {
+	description: Option<String>,
+	media: Option<String>,
+	media_hash: Option<Base64VecU8>,
+	reference: Option<String>,
+	reference_hash: Option<Base64VecU8>,
+	extra: Option<String>,
+}
+
+/// limits checked against the royalty map of every new series
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoyaltyLimits {
+	/// total of all royalties in basis points, at most ROYALTY_BASIS_POINTS
+	max_total: u32,
+	max_receivers: u32,
+}
+/// per-token metadata, the non-empty fields are overlaid onto the series metadata in nft_token
+#[derive(Serialize, Deserialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EditionMetadata {
+	media: Option<String>,
+	media_hash: Option<Base64VecU8>,
+	extra: Option<String>,
+	/// defaults to the block timestamp (milliseconds) when minted
+	issued_at: Option<String>,
+	reference: Option<String>,
+	reference_hash: Option<Base64VecU8>,
 }
 
 /// payout series for royalties to market
@@ -68,7 +167,33 @@ pub struct Contract {
     metadata: LazyOption<NFTContractMetadata>,
 	// CUSTOM
 	token_series_by_title: LookupMap<TokenSeriesTitle, TokenSeriesId>,
-	token_series_by_id: UnorderedMap<TokenSeriesId, TokenSeries>,
+	token_series_by_id: UnorderedMap<TokenSeriesId, VersionedTokenSeries>,
+	series_by_owner: LookupMap<AccountId, UnorderedSet<TokenSeriesId>>,
+	/// account that paid for the token storage at mint, refunded on nft_burn
+	storage_payer_by_id: LookupMap<TokenId, AccountId>,
+	/// NEP-145 prepaid storage, minus the registration cost
+	storage_deposits: LookupMap<AccountId, Balance>,
+	royalty_limits: RoyaltyLimits,
+	/// fungible tokens series owners can price public mints in
+	ft_token_ids: UnorderedSet<AccountId>,
+	/// FT proceeds of public mints whose ft_transfer failed, by receiver then ft_token_id
+	ft_payouts_owed: LookupMap<AccountId, HashMap<AccountId, Balance>>,
+	/// ids are never reused, even after nft_delete_series
+	next_series_id: TokenSeriesId,
+	/// proposed by propose_owner, becomes tokens.owner_id on accept_owner
+	pending_owner_id: Option<AccountId>,
+	admins: UnorderedSet<AccountId>,
+	series_creators: UnorderedSet<AccountId>,
+	/// curated mode, only series creators can call nft_create_series
+	series_creators_only: bool,
+	/// emergency switches, see pause.rs
+	paused: Paused,
+	/// wasm waiting for deploy_upgrade, lazy so it is only read on deploy
+	staged_code: LazyOption<Vec<u8>>,
+	staged_upgrade: Option<StagedUpgrade>,
+	/// nanoseconds holders get to see a staged upgrade before it can be deployed
+	upgrade_timelock: Option<u64>,
+	pending_upgrade_timelock: Option<PendingUpgradeTimelock>,
 }
 const DATA_IMAGE_SVG_NEAR_ICON: &str = "data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 288 288'%3E%3Cg id='l' data-name='l'%3E%3Cpath d='M187.58,79.81l-30.1,44.69a3.2,3.2,0,0,0,4.75,4.2L191.86,103a1.2,1.2,0,0,1,2,.91v80.46a1.2,1.2,0,0,1-2.12.77L102.18,77.93A15.35,15.35,0,0,0,90.47,72.5H87.34A15.34,15.34,0,0,0,72,87.84V201.16A15.34,15.34,0,0,0,87.34,216.5h0a15.35,15.35,0,0,0,13.08-7.31l30.1-44.69a3.2,3.2,0,0,0-4.75-4.2L96.14,186a1.2,1.2,0,0,1-2-.91V104.61a1.2,1.2,0,0,1,2.12-.77l89.55,107.23a15.35,15.35,0,0,0,11.71,5.43h3.13A15.34,15.34,0,0,0,216,201.16V87.84A15.34,15.34,0,0,0,200.66,72.5h0A15.35,15.35,0,0,0,187.58,79.81Z'/%3E%3C/g%3E%3C/svg%3E";
 #[derive(BorshSerialize, BorshStorageKey)]
@@ -83,6 +208,17 @@ enum StorageKey {
     TokenSeriesByTitle,
     TokenSeriesById,
     TokensBySeriesInner { token_series_id: u64 },
+    SeriesByOwner,
+    SeriesByOwnerInner { account_id_hash: CryptoHash },
+    StoragePayerById,
+    FTTokenIds,
+    MintsPerAccountInner { token_series_id: u64 },
+    Admins,
+    SeriesCreators,
+    StagedCode,
+    StorageDeposits,
+    FTPayoutsOwed,
+    StateVersion,
 }
 
 #[near_bindgen]
@@ -107,6 +243,7 @@ impl Contract {
     pub fn new(owner_id: AccountId, metadata: NFTContractMetadata) -> Self {
         assert!(!env::state_exists(), "Already initialized");
         metadata.assert_valid();
+        write_state_version();
         Self {
             tokens: NonFungibleToken::new(
                 StorageKey::NonFungibleToken,
@@ -117,6 +254,25 @@ impl Contract {
             ),
 			token_series_by_id: UnorderedMap::new(StorageKey::TokenSeriesById),
 			token_series_by_title: LookupMap::new(StorageKey::TokenSeriesByTitle),
+			series_by_owner: LookupMap::new(StorageKey::SeriesByOwner),
+			storage_payer_by_id: LookupMap::new(StorageKey::StoragePayerById),
+			storage_deposits: LookupMap::new(StorageKey::StorageDeposits),
+			royalty_limits: RoyaltyLimits {
+				max_total: DEFAULT_ROYALTY_MAX_TOTAL,
+				max_receivers: DEFAULT_ROYALTY_MAX_RECEIVERS,
+			},
+			ft_token_ids: UnorderedSet::new(StorageKey::FTTokenIds),
+			ft_payouts_owed: LookupMap::new(StorageKey::FTPayoutsOwed),
+			next_series_id: 1,
+			pending_owner_id: None,
+			admins: UnorderedSet::new(StorageKey::Admins),
+			series_creators: UnorderedSet::new(StorageKey::SeriesCreators),
+			series_creators_only: false,
+			paused: Paused::default(),
+			staged_code: LazyOption::new(StorageKey::StagedCode, None),
+			staged_upgrade: None,
+			upgrade_timelock: None,
+			pending_upgrade_timelock: None,
             metadata: LazyOption::new(StorageKey::Metadata, Some(&metadata)),
         }
     }
@@ -128,16 +284,19 @@ impl Contract {
         &mut self,
         metadata: TokenMetadata,
         royalty: HashMap<AccountId, u32>,
+        transferable: Option<bool>,
     ) {
+		self.assert_series_creator();
 		let initial_storage_usage = env::storage_usage();
         let owner_id = env::predecessor_account_id();
-		let title = metadata.title.clone();
-		assert!(title.is_some(), "token_metadata.title is required");
-		let token_series_id = self.token_series_by_id.len() + 1;
-        assert!(self.token_series_by_title.insert(&title.unwrap(), &token_series_id).is_none(), "token_metadata.title exists");
-        self.token_series_by_id.insert(&token_series_id, &TokenSeries{
+		let title = metadata.title.clone().expect("token_metadata.title is required");
+		self.assert_valid_royalty(&royalty);
+		let token_series_id = self.next_series_id;
+		self.next_series_id += 1;
+        assert!(self.token_series_by_title.insert(&title, &token_series_id).is_none(), "token_metadata.title exists");
+        self.token_series_by_id.insert(&token_series_id, &VersionedTokenSeries::from(TokenSeries{
 			metadata,
-			owner_id,
+			owner_id: owner_id.clone(),
 			royalty,
 			tokens: UnorderedSet::new(
 				StorageKey::TokensBySeriesInner {
@@ -146,76 +305,465 @@ impl Contract {
 				.try_to_vec()
 				.unwrap(),
 			),
+			num_minted: 0,
 			approved_market_id: None,
-		});
+			approval_id: 0,
+			metadata_frozen: false,
+			pending_owner_id: None,
+			minters: HashMap::new(),
+			public_mint: None,
+			mint_starts_at: None,
+			mint_ends_at: None,
+			max_per_account: None,
+			mints_per_account: LookupMap::new(
+				StorageKey::MintsPerAccountInner {
+					token_series_id
+				}
+				.try_to_vec()
+				.unwrap(),
+			),
+			allowlist: None,
+			transferable: transferable.unwrap_or(true),
+		}));
+		self.internal_add_series_to_owner(&owner_id, token_series_id);
+
+		NftSeriesEventKind::SeriesCreate(&[SeriesCreateData {
+			token_series_id,
+			token_series_title: &title,
+			owner_id: &owner_id,
+			transferable: transferable.unwrap_or(true),
+		}]).emit();
 
-        refund_deposit(env::storage_usage() - initial_storage_usage);
+        self.refund_deposit(env::storage_usage() - initial_storage_usage);
     }
 
+	/// series owner removes a series nothing was minted from, e.g. to fix a typo in the title
+	#[payable]
+	pub fn nft_delete_series(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+	) {
+		assert_one_yocto();
+
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		let owner_id = env::predecessor_account_id();
+		assert_eq!(&owner_id, &token_series.owner_id, "not series owner");
+		assert_eq!(token_series.num_minted, 0, "series has minted tokens");
+
+		self.internal_delete_series(&owner_id, token_series_id, &token_series_title);
+	}
+
 	pub fn cap_copies(
 		&mut self,
 		token_series_title: TokenSeriesTitle,
 	) {
-		assert_eq!(env::predecessor_account_id(), self.tokens.owner_id, "Unauthorized");
+		self.assert_owner_or_admin();
 		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
 		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
-		token_series.metadata.copies = Some(token_series.tokens.len());
+		token_series.metadata.copies = Some(token_series.num_minted);
 		self.token_series_by_id.insert(&token_series_id, &token_series);
 	}
 
+	#[payable]
+	pub fn set_royalty_limits(
+		&mut self,
+		royalty_limits: RoyaltyLimits,
+	) {
+		assert_one_yocto();
+		self.assert_owner_or_admin();
+		assert!(
+			royalty_limits.max_total <= ROYALTY_BASIS_POINTS,
+			"max_total cannot exceed {}",
+			ROYALTY_BASIS_POINTS
+		);
+		assert!(
+			royalty_limits.max_receivers <= MAX_ROYALTY_MAX_RECEIVERS,
+			"max_receivers cannot exceed {}",
+			MAX_ROYALTY_MAX_RECEIVERS
+		);
+		self.royalty_limits = royalty_limits;
+	}
+
+	/// only owner or admin
+	#[payable]
+	pub fn set_contract_metadata(&mut self, metadata: NFTContractMetadata) {
+		assert!(env::attached_deposit() > 0, "Requires attached deposit of at least 1 yoctoNEAR");
+		self.assert_owner_or_admin();
+		let initial_storage_usage = env::storage_usage();
+
+		metadata.assert_valid();
+		self.metadata.set(&metadata);
+
+		self.refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+
+		Nep171EventKind::ContractMetadataUpdate(&[ContractMetadataUpdateData {
+			memo: None,
+		}]).emit();
+	}
+
+	/// series owner bounds when editions can be minted (block timestamps in nanoseconds)
+	/// once mint_ends_at passes the supply is capped at the editions minted
+	#[payable]
+	pub fn nft_set_series_mint_window(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+		mint_starts_at: Option<U64>,
+		mint_ends_at: Option<U64>,
+	) {
+		assert!(env::attached_deposit() > 0, "Requires attached deposit of at least 1 yoctoNEAR");
+		let initial_storage_usage = env::storage_usage();
+
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		assert_eq!(&env::predecessor_account_id(), &token_series.owner_id, "not series owner");
+		if let Some(mint_ends_at) = token_series.mint_ends_at {
+			assert!(env::block_timestamp() < mint_ends_at, "series minting ended");
+		}
+		if let (Some(mint_starts_at), Some(mint_ends_at)) = (mint_starts_at, mint_ends_at) {
+			assert!(mint_starts_at.0 < mint_ends_at.0, "mint_starts_at must be before mint_ends_at");
+		}
+
+		token_series.mint_starts_at = mint_starts_at.map(|v| v.0);
+		token_series.mint_ends_at = mint_ends_at.map(|v| v.0);
+		self.token_series_by_id.insert(&token_series_id, &token_series);
+
+		self.refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+	}
+
+	/// series owner sets the limit before the first mint, mints are only counted while a limit is set
+	#[payable]
+	pub fn nft_set_series_max_per_account(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+		max_per_account: Option<u32>,
+	) {
+		assert!(env::attached_deposit() > 0, "Requires attached deposit of at least 1 yoctoNEAR");
+		let initial_storage_usage = env::storage_usage();
+
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		assert_eq!(&env::predecessor_account_id(), &token_series.owner_id, "not series owner");
+		assert_eq!(token_series.num_minted, 0, "series has minted tokens");
+
+		token_series.max_per_account = max_per_account;
+		self.token_series_by_id.insert(&token_series_id, &token_series);
+
+		self.refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+	}
+
+	/// series owner can change the artwork and description until the metadata is frozen
+	#[payable]
+	pub fn nft_update_series_metadata(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+		metadata: SeriesMetadataUpdate,
+	) {
+		assert!(env::attached_deposit() > 0, "Requires attached deposit of at least 1 yoctoNEAR");
+		let initial_storage_usage = env::storage_usage();
+
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		assert_eq!(&env::predecessor_account_id(), &token_series.owner_id, "not series owner");
+		assert!(!token_series.metadata_frozen, "series metadata frozen");
+
+		let SeriesMetadataUpdate { description, media, media_hash, reference, reference_hash, extra } = metadata;
+		if description.is_some() {
+			token_series.metadata.description = description;
+		}
+		if media.is_some() {
+			token_series.metadata.media = media;
+			token_series.metadata.media_hash = media_hash;
+		}
+		if reference.is_some() {
+			token_series.metadata.reference = reference;
+			token_series.metadata.reference_hash = reference_hash;
+		}
+		if extra.is_some() {
+			token_series.metadata.extra = extra;
+		}
+		token_series.metadata.updated_at = Some((env::block_timestamp() / 1_000_000).to_string());
+		self.token_series_by_id.insert(&token_series_id, &token_series);
+
+		self.refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+
+		NftSeriesEventKind::SeriesUpdate(&[SeriesUpdateData {
+			token_series_id,
+			token_series_title: &token_series_title,
+			updated_at: token_series.metadata.updated_at.as_deref(),
+			metadata_frozen: token_series.metadata_frozen,
+		}]).emit();
+	}
+
+	/// one-way, after this the series metadata can never be updated
+	#[payable]
+	pub fn nft_freeze_series_metadata(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+	) {
+		assert_one_yocto();
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		assert_eq!(&env::predecessor_account_id(), &token_series.owner_id, "not series owner");
+		assert!(!token_series.metadata_frozen, "series metadata frozen");
+
+		token_series.metadata_frozen = true;
+		self.token_series_by_id.insert(&token_series_id, &token_series);
+
+		NftSeriesEventKind::SeriesUpdate(&[SeriesUpdateData {
+			token_series_id,
+			token_series_title: &token_series_title,
+			updated_at: token_series.metadata.updated_at.as_deref(),
+			metadata_frozen: true,
+		}]).emit();
+	}
+
+	/// step 1 of handing a series to another account, replaces any pending proposal
+	#[payable]
+	pub fn nft_transfer_series_ownership(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+		new_owner_id: AccountId,
+	) {
+		assert_one_yocto();
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		assert_eq!(&env::predecessor_account_id(), &token_series.owner_id, "not series owner");
+		assert_ne!(&new_owner_id, &token_series.owner_id, "already series owner");
+
+		token_series.pending_owner_id = Some(new_owner_id.clone());
+		self.token_series_by_id.insert(&token_series_id, &token_series);
+
+		NftSeriesEventKind::SeriesTransferPropose(&[SeriesTransferData {
+			token_series_id,
+			old_owner_id: &token_series.owner_id,
+			new_owner_id: &new_owner_id,
+		}]).emit();
+	}
+
+	/// step 2, the proposed owner accepts and pays for any storage of the owner index
+	#[payable]
+	pub fn nft_accept_series_ownership(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+	) {
+		assert!(env::attached_deposit() > 0, "Requires attached deposit of at least 1 yoctoNEAR");
+		let initial_storage_usage = env::storage_usage();
+
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		let new_owner_id = env::predecessor_account_id();
+		assert_eq!(Some(&new_owner_id), token_series.pending_owner_id.as_ref(), "not pending series owner");
+
+		let old_owner_id = token_series.owner_id.clone();
+		self.internal_remove_series_from_owner(&old_owner_id, token_series_id);
+		self.internal_add_series_to_owner(&new_owner_id, token_series_id);
+
+		token_series.owner_id = new_owner_id.clone();
+		token_series.pending_owner_id = None;
+		// the new owner approves their own market, grants their own minters and splits their own sales
+		token_series.approved_market_id = None;
+		token_series.minters.clear();
+		if let Some(public_mint) = token_series.public_mint.as_mut() {
+			public_mint.split = None;
+		}
+		self.token_series_by_id.insert(&token_series_id, &token_series);
+
+		self.refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+
+		NftSeriesEventKind::SeriesTransfer(&[SeriesTransferData {
+			token_series_id,
+			old_owner_id: &old_owner_id,
+			new_owner_id: &new_owner_id,
+		}]).emit();
+	}
+
+	/// approve a market to sell (lazy mint) editions of the series
+	/// replaces any previously approved market
+	#[payable]
+	pub fn nft_series_approve(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+		account_id: AccountId,
+		msg: Option<String>,
+	) -> Option<Promise> {
+		assert!(env::attached_deposit() > 0, "Requires attached deposit of at least 1 yoctoNEAR");
+		let initial_storage_usage = env::storage_usage();
+
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		let owner_id = env::predecessor_account_id();
+		assert_eq!(&owner_id, &token_series.owner_id, "not series owner");
+
+		token_series.approval_id += 1;
+		token_series.approved_market_id = Some(account_id.clone());
+		let approval_id = token_series.approval_id;
+		self.token_series_by_id.insert(&token_series_id, &token_series);
+
+		self.refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+
+		msg.map(|msg| {
+			ext_series_approval_receiver::series_on_approve(
+				token_series_title,
+				owner_id,
+				approval_id,
+				msg,
+				account_id,
+				NO_DEPOSIT,
+				env::prepaid_gas() - GAS_FOR_SERIES_APPROVE,
+			)
+		})
+	}
+
+	/// series owner lets account_id mint, replaces any existing grant for account_id
+	#[payable]
+	pub fn nft_grant_series_minter(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+		account_id: AccountId,
+		quota: Option<u32>,
+		expires_at: Option<U64>,
+	) {
+		assert!(env::attached_deposit() > 0, "Requires attached deposit of at least 1 yoctoNEAR");
+		let initial_storage_usage = env::storage_usage();
+
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		assert_eq!(&env::predecessor_account_id(), &token_series.owner_id, "not series owner");
+
+		let grant = MinterGrant { quota, expires_at };
+		token_series.minters.insert(account_id.clone(), grant.clone());
+		self.token_series_by_id.insert(&token_series_id, &token_series);
+
+		self.refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+
+		NftSeriesEventKind::SeriesMinterGrant(&[SeriesMinterGrantData {
+			token_series_id,
+			account_id: &account_id,
+			quota: grant.quota,
+			expires_at: grant.expires_at,
+		}]).emit();
+	}
+
+	#[payable]
+	pub fn nft_revoke_series_minter(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+		account_id: AccountId,
+	) {
+		assert_one_yocto();
+		let initial_storage_usage = env::storage_usage();
+
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		let owner_id = env::predecessor_account_id();
+		assert_eq!(&owner_id, &token_series.owner_id, "not series owner");
+
+		token_series.minters.remove(&account_id).expect("not series minter");
+		self.token_series_by_id.insert(&token_series_id, &token_series);
+
+		self.refund_storage(&owner_id, initial_storage_usage.saturating_sub(env::storage_usage()));
+
+		NftSeriesEventKind::SeriesMinterRevoke(&[SeriesMinterRevokeData {
+			token_series_id,
+			account_id: &account_id,
+		}]).emit();
+	}
+
 	#[payable]
 	pub fn nft_mint_series(
 		&mut self,
 		token_series_title: TokenSeriesTitle,
 		receiver_id: AccountId,
+		edition_metadata: Option<EditionMetadata>,
+	) -> Token {
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		self.nft_mint_series_by_id(token_series_id.into(), receiver_id, edition_metadata)
+	}
+
+	#[payable]
+	pub fn nft_mint_series_by_id(
+		&mut self,
+		token_series_id: U64,
+		receiver_id: AccountId,
+		edition_metadata: Option<EditionMetadata>,
 	) -> Token {
 		let initial_storage_usage = env::storage_usage();
 
+		let token_series_id = token_series_id.0;
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no series");
+		assert_series_minter(&token_series, 1);
+		use_minter_quota(&mut token_series, 1);
+
+		let token = self.internal_mint_series(token_series_id, &mut token_series, receiver_id, &env::predecessor_account_id(), edition_metadata);
+
+        self.refund_deposit(env::storage_usage() - initial_storage_usage);
+
+		token
+	}
+
+	/// mints count sequential editions to each receiver, returns how many were minted
+	/// stops early when running out of gas, call again with the remaining receivers
+	#[payable]
+	pub fn nft_batch_mint_series(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+		receivers: Vec<(AccountId, u32)>,
+	) -> u32 {
+		let initial_storage_usage = env::storage_usage();
+
 		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
 		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
-		assert_eq!(&env::predecessor_account_id(), &token_series.owner_id, "not series owner");
 
-		let num_tokens = token_series.tokens.len();
+		let num_to_mint: u64 = receivers.iter().map(|(_, count)| *count as u64).sum();
+		assert_series_minter(&token_series, num_to_mint);
+		assert_mint_window(&token_series);
 		let max_copies = token_series.metadata.copies.unwrap_or(u64::MAX);
-		assert_ne!(num_tokens, max_copies, "series supply maxed");
+		assert!(
+			num_to_mint <= max_copies.saturating_sub(token_series.num_minted),
+			"series supply maxed"
+		);
 
-		let token_id = format!("{}{}{}", &token_series_id, TOKEN_DELIMETER, num_tokens + 1);
-		token_series.tokens.insert(&token_id);
+		let storage_payer_id = env::predecessor_account_id();
+		let mut minted = 0;
+		let mut out_of_gas = false;
+		for (receiver_id, count) in receivers {
+			let mut token_ids = vec![];
+			for _ in 0..count {
+				if env::prepaid_gas() - env::used_gas() < GAS_FOR_BATCH_MINT_EDITION {
+					out_of_gas = true;
+					break;
+				}
+				let token = self.internal_mint_edition(token_series_id, &mut token_series, receiver_id.clone(), &storage_payer_id, None);
+				token_ids.push(token.token_id);
+				minted += 1;
+			}
+			if !token_ids.is_empty() {
+				log_nft_mint(&receiver_id, &token_ids);
+			}
+			if out_of_gas {
+				break;
+			}
+		}
+		use_minter_quota(&mut token_series, minted as u64);
 		self.token_series_by_id.insert(&token_series_id, &token_series);
 
-		// you can add custom metadata to each token here
-		// make sure you update self.nft_token to "patch" over the series metadata
-		let metadata = Some(TokenMetadata {
-			title: None, // ex. "Arch Nemesis: Mail Carrier" or "Parcel #5055"
-			description: None, // free-form description
-			media: None, // URL to associated media, preferably to decentralized, content-addressed storage
-			copies: None, // number of copies of this set of metadata in existence when token was minted.
-			media_hash: None, // Base64-encoded sha256 hash of content referenced by the `media` field. Required if `media` is included.
-			issued_at: None, // ISO 8601 datetime when token was issued or minted
-			expires_at: None, // ISO 8601 datetime when token expires
-			starts_at: None, // ISO 8601 datetime when token starts being valid
-			updated_at: None, // ISO 8601 datetime when token was last updated
-			extra: None, // anything extra the NFT wants to store on-chain. Can be stringified JSON.
-			reference: None, // URL to an off-chain JSON file with more info.
-			reference_hash: None, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
-		});
-		let token = self.tokens.internal_mint(token_id.clone(), receiver_id.clone(), metadata);
-
-        refund_deposit(env::storage_usage() - initial_storage_usage);
-
-		env::log_str(format!("{}{}", EVENT_JSON, json!({
-			"standard": "nep171",
-			"version": "1.0.0",
-			"event": "nft_mint",
-			"data": [
-			  	{
-					  "owner_id": receiver_id,
-					  "token_ids": [token_id]
-				}
-			]
-		})).as_ref());
-			
-		token
+        self.refund_deposit(env::storage_usage() - initial_storage_usage);
+
+		minted
+	}
+
+	/// token owner destroys the token, storage is refunded to whoever paid for it
+	#[payable]
+	pub fn nft_burn(
+		&mut self,
+		token_id: TokenId,
+	) {
+		assert_one_yocto();
+		let owner_id = self.tokens.owner_by_id.get(&token_id).expect("no token");
+		assert_eq!(&env::predecessor_account_id(), &owner_id, "not token owner");
+
+		self.internal_burn(&owner_id, &token_id);
 	}
 
 	/// CUSTOM re-implement core standard here, not using macros from near-contract-standards
@@ -229,7 +777,11 @@ impl Contract {
 		approval_id: Option<u64>,
 		memo: Option<String>,
 	) {
-		self.tokens.nft_transfer(receiver_id, token_id, approval_id, memo)
+		self.assert_not_paused(PauseFeature::Transfer);
+		self.assert_transferable(&token_id);
+		let owner_id = self.tokens.owner_by_id.get(&token_id).expect("no token");
+		self.tokens.nft_transfer(receiver_id.clone(), token_id.clone(), approval_id, memo.clone());
+		log_nft_transfer(&owner_id, &receiver_id, &token_id, memo.as_deref());
 	}
 
 	/// pass through
@@ -242,6 +794,10 @@ impl Contract {
 		memo: Option<String>,
 		msg: String,
 	) -> PromiseOrValue<bool> {
+		self.assert_not_paused(PauseFeature::Transfer);
+		self.assert_transferable(&token_id);
+		let owner_id = self.tokens.owner_by_id.get(&token_id).expect("no token");
+		log_nft_transfer(&owner_id, &receiver_id, &token_id, memo.as_deref());
 		self.tokens.nft_transfer_call(receiver_id, token_id, approval_id, memo, msg)
 	}
 
@@ -256,61 +812,48 @@ impl Contract {
 		balance: Option<U128>,
 		max_len_payout: Option<u32>,
 	) -> Option<Payout> {
+		self.assert_not_paused(PauseFeature::Payout);
+
+		// lazy minting? token_id is a series title when the approved market sells the next edition
+		let (token_id, owner_id) = if self.tokens.owner_by_id.get(&token_id).is_none() {
+			let token_series_id = self.token_series_by_title.get(&token_id).expect("no token");
+			let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no series");
+			assert_eq!(
+				Some(env::predecessor_account_id()),
+				token_series.approved_market_id,
+				"not approved market"
+			);
+			assert_eq!(approval_id, token_series.approval_id, "invalid approval_id");
+			assert_public_phase(&token_series);
 
-		// lazy minting?
-		let series_mint_args = memo.clone();
-		let previous_token = if let Some(series_mint_args) = series_mint_args {
-			let SeriesMintArgs{token_series_title, receiver_id} = near_sdk::serde_json::from_str(&series_mint_args).expect("invalid SeriesMintArgs");
-			self.nft_mint_series(token_series_title, receiver_id.clone())
+			let initial_storage_usage = env::storage_usage();
+			let token = self.internal_mint_series(token_series_id, &mut token_series, receiver_id.clone(), &env::predecessor_account_id(), None);
+			self.refund_deposit(env::storage_usage() - initial_storage_usage);
+
+			(token.token_id, token_series.owner_id.clone())
 		} else {
-			let prev_token = self.nft_token(token_id.clone()).expect("no token");
-			self.tokens.nft_transfer(receiver_id.clone(), token_id.clone(), Some(approval_id), memo);
-			prev_token
+			let owner_id = self.tokens.owner_by_id.get(&token_id).expect("no token");
+			self.assert_transferable(&token_id);
+			self.tokens.nft_transfer(receiver_id.clone(), token_id.clone(), Some(approval_id), memo.clone());
+
+			log_nft_transfer(&owner_id, &receiver_id, &token_id, memo.as_deref());
+
+			(token_id, owner_id)
 		};
 
         // compute payouts based on balance option
-        let owner_id = previous_token.owner_id;
-        let payout_struct = if let Some(balance) = balance {
-			let complete_royalty = 10_000u128;
-            let balance_piece = u128::from(balance) / complete_royalty;
-			let mut total_royalty_percentage = 0;
-            let mut payout_struct: Payout = Payout{
-				payout: HashMap::new()
-			};
-			let mut token_id_iter = token_id.split(TOKEN_DELIMETER);
-			let token_series_id = token_id_iter.next().unwrap().parse().unwrap();
-            let royalty = self.token_series_by_id.get(&token_series_id).expect("no series").royalty;
-
-            if let Some(max_len_payout) = max_len_payout {
-                assert!(royalty.len() as u32 <= max_len_payout, "exceeds max_len_payout");
-            }
-            for (k, v) in royalty.iter() {
-                let key = k.clone();
-				// skip seller and payout once at end
-                if key != owner_id {
-                    payout_struct.payout.insert(key, U128(*v as u128 * balance_piece));
-                    total_royalty_percentage += *v;
-                }
-            }
-            // payout to seller
-            payout_struct.payout.insert(owner_id.clone(), U128((complete_royalty - total_royalty_percentage as u128) * balance_piece));
-            Some(payout_struct)
-        } else {
-            None
-        };
-
-		env::log_str(format!("{}{}", EVENT_JSON, json!({
-			"standard": "nep171",
-			"version": "1.0.0",
-			"event": "nft_transfer",
-			"data": [
-				{
-					"old_owner_id": owner_id, "new_owner_id": receiver_id, "token_ids": [token_id]
-				}
-			]
-		})).as_ref());
+		balance.map(|balance| self.internal_payout(&token_id, &owner_id, balance, max_len_payout))
+	}
 
-        payout_struct
+	/// NEP-199 payout for token_id at balance, without transferring the token
+	pub fn nft_payout(
+		&self,
+		token_id: TokenId,
+		balance: U128,
+		max_len_payout: u32,
+	) -> Payout {
+		let owner_id = self.tokens.owner_by_id.get(&token_id).expect("no token");
+		self.internal_payout(&token_id, &owner_id, balance, Some(max_len_payout))
 	}
 
 	/// CUSTOM re-implementation of near-contract-standards (not using macros)
@@ -328,8 +871,9 @@ impl Contract {
 		let mut token_id_iter = token_id.split(TOKEN_DELIMETER);
 		let token_series_id = token_id_iter.next().unwrap().parse().unwrap();
 		// make edition titles nice for showing in wallet
-		let mut metadata = self.token_series_by_id.get(&token_series_id).unwrap().metadata;
-		let copies = metadata.copies;
+		let token_series = self.token_series_by_id.get(&token_series_id).unwrap().into_inner();
+		let copies = series_copies(&token_series);
+		let mut metadata = token_series.metadata;
 		if let Some(copies) = copies {
 			metadata.title = Some(
 				format!(
@@ -343,11 +887,20 @@ impl Contract {
 			);
 		}
 		
-		// CUSTOM
-		// implement this if you need to combine individual token metadata
-		// e.g. metadata.extra with TokenSeries.metadata.extra and return something unique
-		// let token_metadata = self.tokens.token_metadata_by_id.get(&token_id)?;
-		// metadata.extra = token_metadata.extra;
+		// CUSTOM overlay the individual token metadata (if any) onto the series metadata
+		if let Some(token_metadata) = self.tokens.token_metadata_by_id.as_ref().and_then(|by_id| by_id.get(&token_id)) {
+			// hashes always belong to the file of the same metadata
+			if token_metadata.media.is_some() {
+				metadata.media = token_metadata.media;
+				metadata.media_hash = token_metadata.media_hash;
+			}
+			metadata.extra = token_metadata.extra.or(metadata.extra);
+			metadata.issued_at = token_metadata.issued_at.or(metadata.issued_at);
+			if token_metadata.reference.is_some() {
+				metadata.reference = token_metadata.reference;
+				metadata.reference_hash = token_metadata.reference_hash;
+			}
+		}
 
         Some(Token { token_id, owner_id, metadata: Some(metadata), approved_account_ids })
 	}
@@ -398,12 +951,12 @@ impl Contract {
 	/// CUSTOM VIEWS for seriesd tokens
 
 	pub fn nft_get_series_json(&self, token_series_title: TokenSeriesTitle) -> TokenSeriesJson {
-		let token_series = self.token_series_by_id.get(&self.token_series_by_title.get(&token_series_title).expect("no series")).expect("no series");
-		TokenSeriesJson{
-			metadata: token_series.metadata,
-			owner_id: token_series.owner_id,
-			royalty: token_series.royalty,
-		}
+		self.nft_get_series_json_by_id(self.token_series_by_title.get(&token_series_title).expect("no series").into())
+	}
+
+	pub fn nft_get_series_json_by_id(&self, token_series_id: U64) -> TokenSeriesJson {
+		let token_series = self.token_series_by_id.get(&token_series_id.0).expect("no series");
+		series_json(token_series_id.0, token_series.into_inner())
 	}
 
 	pub fn nft_get_series(
@@ -411,36 +964,105 @@ impl Contract {
 		from_index: Option<U128>,
 		limit: Option<u64>
 	) -> Vec<TokenSeriesJson> {
-		unordered_map_val_pagination(&self.token_series_by_id, from_index, limit)
-			.iter()
-			.map(|token_series| TokenSeriesJson{
-				metadata: token_series.metadata.clone(),
-				owner_id: token_series.owner_id.clone(),
-				royalty: token_series.royalty.clone(),
-			})
+		unordered_map_key_pagination(&self.token_series_by_id, from_index, limit)
+			.into_iter()
+			.zip(unordered_map_val_pagination(&self.token_series_by_id, from_index, limit))
+			.map(|(token_series_id, token_series)| series_json(token_series_id, token_series.into_inner()))
             .collect()
     }
 
+	pub fn nft_series_supply_for_owner(
+		&self,
+		account_id: AccountId,
+	) -> U64 {
+		self.series_by_owner
+			.get(&account_id)
+			.map(|series_set| series_set.len())
+			.unwrap_or(0)
+			.into()
+	}
+
+	pub fn nft_series_for_owner(
+		&self,
+		account_id: AccountId,
+		from_index: Option<U128>,
+		limit: Option<u64>,
+	) -> Vec<TokenSeriesJson> {
+		let series_set = if let Some(series_set) = self.series_by_owner.get(&account_id) {
+			series_set
+		} else {
+			return vec![];
+		};
+		unordered_set_pagination(&series_set, from_index, limit)
+			.into_iter()
+			.map(|token_series_id| series_json(token_series_id, self.token_series_by_id.get(&token_series_id).unwrap().into_inner()))
+			.collect()
+	}
+
 	pub fn nft_supply_for_series(
         &self,
         token_series_title: TokenSeriesTitle,
     ) -> U64 {
-        self.token_series_by_id.get(&self.token_series_by_title.get(&token_series_title).expect("no series")).expect("no series").tokens.len().into()
+        self.nft_supply_for_series_by_id(self.token_series_by_title.get(&token_series_title).expect("no series").into())
     }
 
+	pub fn nft_supply_for_series_by_id(
+        &self,
+        token_series_id: U64,
+    ) -> U64 {
+        self.token_series_by_id.get(&token_series_id.0).expect("no series").tokens.len().into()
+    }
+
+	/// editions account_id can still receive from the series, None is unlimited
+	pub fn nft_series_mints_left(
+		&self,
+		token_series_title: TokenSeriesTitle,
+		account_id: AccountId,
+	) -> Option<u32> {
+		let token_series = self.token_series_by_id.get(&self.token_series_by_title.get(&token_series_title).expect("no series")).expect("no series");
+		token_series.max_per_account.map(|max_per_account| {
+			max_per_account.saturating_sub(token_series.mints_per_account.get(&account_id).unwrap_or(0))
+		})
+	}
+
+	/// minters whose grant has not expired or run out of quota
+	pub fn nft_series_minters(
+		&self,
+		token_series_title: TokenSeriesTitle,
+	) -> HashMap<AccountId, MinterGrant> {
+		let token_series = self.token_series_by_id.get(&self.token_series_by_title.get(&token_series_title).expect("no series")).expect("no series").into_inner();
+		token_series.minters
+			.into_iter()
+			.filter(|(_, grant)| grant_is_active(grant, 1))
+			.collect()
+	}
+
 	pub fn nft_tokens_by_series(
 		&self,
         token_series_title: TokenSeriesTitle,
 		from_index: Option<U128>,
 		limit: Option<u64>
 	) -> Vec<Token> {
-		let tokens = self.token_series_by_id.get(&self.token_series_by_title.get(&token_series_title).expect("no series")).expect("no series").tokens;
+		self.nft_tokens_by_series_id(self.token_series_by_title.get(&token_series_title).expect("no series").into(), from_index, limit)
+	}
+
+	pub fn nft_tokens_by_series_id(
+		&self,
+		token_series_id: U64,
+		from_index: Option<U128>,
+		limit: Option<u64>
+	) -> Vec<Token> {
+		let tokens = self.token_series_by_id.get(&token_series_id.0).expect("no series").into_inner().tokens;
 		unordered_set_pagination(&tokens, from_index, limit)
 			.iter()
 			.map(|token_id| self.nft_token(token_id.clone()).unwrap())
             .collect()
     }
 
+	pub fn get_royalty_limits(&self) -> &RoyaltyLimits {
+		&self.royalty_limits
+	}
+
 	pub fn nft_get_series_format(&self) -> (char, &'static str, &'static str) {
 		(TOKEN_DELIMETER, TITLE_DELIMETER, EDITION_DELIMETER)
 	}
@@ -449,7 +1071,70 @@ impl Contract {
 // near-contract-standards macros
 // near_contract_standards::impl_non_fungible_token_core!(Contract, tokens);
 // near_contract_standards::impl_non_fungible_token_enumeration!(Contract, tokens);
-near_contract_standards::impl_non_fungible_token_approval!(Contract, tokens);
+// near_contract_standards::impl_non_fungible_token_approval!(Contract, tokens);
+
+#[near_bindgen]
+impl NonFungibleTokenApproval for Contract {
+	/// CUSTOM storage comes from the attached deposit or the owner's storage balance
+	#[payable]
+	fn nft_approve(
+		&mut self,
+		token_id: TokenId,
+		account_id: AccountId,
+		msg: Option<String>,
+	) -> Option<Promise> {
+		assert!(env::attached_deposit() > 0, "Requires attached deposit of at least 1 yoctoNEAR");
+		let initial_storage_usage = env::storage_usage();
+
+		let owner_id = self.tokens.owner_by_id.get(&token_id).expect("no token");
+		assert_eq!(&env::predecessor_account_id(), &owner_id, "Predecessor must be token owner.");
+		self.assert_transferable(&token_id);
+
+		let approvals_by_id = self.tokens.approvals_by_id.as_mut().unwrap();
+		let next_approval_id_by_id = self.tokens.next_approval_id_by_id.as_mut().unwrap();
+		let mut approved_account_ids = approvals_by_id.get(&token_id).unwrap_or_default();
+		let approval_id = next_approval_id_by_id.get(&token_id).unwrap_or(1);
+		approved_account_ids.insert(account_id.clone(), approval_id);
+		approvals_by_id.insert(&token_id, &approved_account_ids);
+		next_approval_id_by_id.insert(&token_id, &(approval_id + 1));
+
+		self.refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+
+		msg.map(|msg| {
+			ext_nft_approval_receiver::nft_on_approve(
+				token_id,
+				owner_id,
+				approval_id,
+				msg,
+				account_id,
+				NO_DEPOSIT,
+				env::prepaid_gas() - GAS_FOR_NFT_APPROVE,
+			)
+		})
+	}
+
+	/// pass through
+	#[payable]
+	fn nft_revoke(&mut self, token_id: TokenId, account_id: AccountId) {
+		self.tokens.nft_revoke(token_id, account_id)
+	}
+
+	/// pass through
+	#[payable]
+	fn nft_revoke_all(&mut self, token_id: TokenId) {
+		self.tokens.nft_revoke_all(token_id)
+	}
+
+	/// pass through
+	fn nft_is_approved(
+		&self,
+		token_id: TokenId,
+		approved_account_id: AccountId,
+		approval_id: Option<u64>,
+	) -> bool {
+		self.tokens.nft_is_approved(token_id, approved_account_id, approval_id)
+	}
+}
 
 #[near_bindgen]
 impl NonFungibleTokenMetadataProvider for Contract {
@@ -468,11 +1153,22 @@ impl NonFungibleTokenResolver for Contract {
 		token_id: TokenId,
 		approved_account_ids: Option<HashMap<AccountId, u64>>,
 	) -> bool {
-		self.tokens.nft_resolve_transfer(
-			previous_owner_id,
-			receiver_id,
-			token_id,
+		let transferred = self.tokens.nft_resolve_transfer(
+			previous_owner_id.clone(),
+			receiver_id.clone(),
+			token_id.clone(),
 			approved_account_ids,
-		)
+		);
+		// the receiver returned the token
+		if !transferred {
+			Nep171EventKind::NftTransfer(&[NftTransferData {
+				authorized_id: None,
+				old_owner_id: &receiver_id,
+				new_owner_id: &previous_owner_id,
+				token_ids: vec![&token_id],
+				memo: None,
+			}]).emit();
+		}
+		transferred
 	}
 }
\ No newline at end of file
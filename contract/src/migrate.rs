@@ -0,0 +1,311 @@
+use crate::*;
+use std::io;
+use std::ops::{Deref, DerefMut};
+
+/// written in front of every stored TokenSeries, the untagged layout of ContractV1 is version 1
+/// bump when a deployed TokenSeries layout changes and upgrade the previous layout in deserialize
+const TOKEN_SERIES_VERSION: u8 = 2;
+/// written under StorageKey::StateVersion, ContractV1 never wrote it and is version 1
+/// bump when a deployed Contract layout changes and upgrade the previous layout in migrate
+const STATE_VERSION: u8 = 2;
+
+/// TokenSeries as stored in token_series_by_id
+pub struct VersionedTokenSeries(TokenSeries);
+
+impl VersionedTokenSeries {
+	pub fn into_inner(self) -> TokenSeries {
+		self.0
+	}
+}
+
+impl From<TokenSeries> for VersionedTokenSeries {
+	fn from(token_series: TokenSeries) -> Self {
+		Self(token_series)
+	}
+}
+
+impl Deref for VersionedTokenSeries {
+	type Target = TokenSeries;
+	fn deref(&self) -> &TokenSeries {
+		&self.0
+	}
+}
+
+impl DerefMut for VersionedTokenSeries {
+	fn deref_mut(&mut self) -> &mut TokenSeries {
+		&mut self.0
+	}
+}
+
+impl BorshSerialize for VersionedTokenSeries {
+	fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+		BorshSerialize::serialize(&TOKEN_SERIES_VERSION, writer)?;
+		self.0.serialize(writer)
+	}
+}
+
+impl BorshDeserialize for VersionedTokenSeries {
+	fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+		match <u8 as BorshDeserialize>::deserialize(buf)? {
+			TOKEN_SERIES_VERSION => Ok(Self(TokenSeries::deserialize(buf)?)),
+			version => Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				format!("unknown TokenSeries version {}", version),
+			)),
+		}
+	}
+}
+
+/// TokenSeries layout before versioning
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct TokenSeriesV1 {
+	metadata: TokenMetadata,
+	owner_id: AccountId,
+	royalty: HashMap<AccountId, u32>,
+	tokens: UnorderedSet<TokenId>,
+	approved_market_id: Option<AccountId>,
+}
+
+impl TokenSeriesV1 {
+	/// nothing was burnable, so every token ever minted is still in tokens
+	pub fn upgrade(self, token_series_id: TokenSeriesId) -> TokenSeries {
+		let TokenSeriesV1 { metadata, owner_id, royalty, tokens, approved_market_id } = self;
+		TokenSeries {
+			metadata,
+			owner_id,
+			royalty,
+			num_minted: tokens.len(),
+			tokens,
+			approved_market_id,
+			approval_id: 0,
+			metadata_frozen: false,
+			pending_owner_id: None,
+			minters: HashMap::new(),
+			public_mint: None,
+			mint_starts_at: None,
+			mint_ends_at: None,
+			max_per_account: None,
+			mints_per_account: LookupMap::new(
+				StorageKey::MintsPerAccountInner { token_series_id }
+				.try_to_vec()
+				.unwrap(),
+			),
+			allowlist: None,
+			transferable: true,
+		}
+	}
+}
+
+/// Contract layout before state versions
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ContractV1 {
+	tokens: NonFungibleToken,
+	metadata: LazyOption<NFTContractMetadata>,
+	token_series_by_title: LookupMap<TokenSeriesTitle, TokenSeriesId>,
+	token_series_by_id: UnorderedMap<TokenSeriesId, TokenSeriesV1>,
+}
+
+/// kept outside of the Contract state so migrate knows the layout before reading it
+pub(crate) fn state_version() -> u8 {
+	LazyOption::<u8>::new(StorageKey::StateVersion, None).get().unwrap_or(1)
+}
+
+pub(crate) fn write_state_version() {
+	LazyOption::new(StorageKey::StateVersion, Some(&STATE_VERSION));
+}
+
+#[near_bindgen]
+impl Contract {
+	/// call right after deploying new code, a no-op when the state is already the current version
+	#[private]
+	#[init(ignore_state)]
+	pub fn migrate() -> Self {
+		let mut this: Self = match state_version() {
+			STATE_VERSION => env::state_read().expect("no state"),
+			1 => Self::migrate_v1(),
+			version => panic!("unknown state version {}", version),
+		};
+		write_state_version();
+		this.internal_finish_upgrade();
+
+		this
+	}
+}
+
+impl Contract {
+	/// rewrites every series so gas grows with the number of series
+	fn migrate_v1() -> Self {
+		let ContractV1 {
+			tokens,
+			metadata,
+			token_series_by_title,
+			token_series_by_id: mut old_token_series_by_id,
+		} = env::state_read().expect("no state");
+
+		let mut this = Self {
+			tokens,
+			metadata,
+			token_series_by_title,
+			token_series_by_id: UnorderedMap::new(StorageKey::TokenSeriesById),
+			series_by_owner: LookupMap::new(StorageKey::SeriesByOwner),
+			storage_payer_by_id: LookupMap::new(StorageKey::StoragePayerById),
+			storage_deposits: LookupMap::new(StorageKey::StorageDeposits),
+			royalty_limits: RoyaltyLimits {
+				max_total: DEFAULT_ROYALTY_MAX_TOTAL,
+				max_receivers: DEFAULT_ROYALTY_MAX_RECEIVERS,
+			},
+			ft_token_ids: UnorderedSet::new(StorageKey::FTTokenIds),
+			ft_payouts_owed: LookupMap::new(StorageKey::FTPayoutsOwed),
+			// ids were len() + 1 and series could not be deleted
+			next_series_id: old_token_series_by_id.len() + 1,
+			pending_owner_id: None,
+			admins: UnorderedSet::new(StorageKey::Admins),
+			series_creators: UnorderedSet::new(StorageKey::SeriesCreators),
+			series_creators_only: false,
+			paused: Paused::default(),
+			staged_code: LazyOption::new(StorageKey::StagedCode, None),
+			staged_upgrade: None,
+			upgrade_timelock: None,
+			pending_upgrade_timelock: None,
+		};
+
+		// same storage prefix, clear the untagged records before writing them back tagged
+		let old_series = old_token_series_by_id.to_vec();
+		old_token_series_by_id.clear();
+		for (token_series_id, token_series) in old_series {
+			this.internal_add_series_to_owner(&token_series.owner_id, token_series_id);
+			this.token_series_by_id.insert(&token_series_id, &token_series.upgrade(token_series_id).into());
+		}
+
+		this
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use near_sdk::test_utils::{accounts, VMContextBuilder};
+	use near_sdk::testing_env;
+
+	fn old_metadata(title: &str) -> TokenMetadata {
+		TokenMetadata {
+			title: Some(title.to_string()),
+			description: None,
+			media: None,
+			media_hash: None,
+			copies: Some(10),
+			issued_at: None,
+			expires_at: None,
+			starts_at: None,
+			updated_at: None,
+			extra: None,
+			reference: None,
+			reference_hash: None,
+		}
+	}
+
+	fn old_series(token_series_id: TokenSeriesId, title: &str) -> TokenSeriesV1 {
+		let mut tokens = UnorderedSet::new(
+			StorageKey::TokensBySeriesInner { token_series_id }
+			.try_to_vec()
+			.unwrap(),
+		);
+		tokens.insert(&format!("{}{}1", token_series_id, TOKEN_DELIMETER));
+		tokens.insert(&format!("{}{}2", token_series_id, TOKEN_DELIMETER));
+		TokenSeriesV1 {
+			metadata: old_metadata(title),
+			owner_id: accounts(1),
+			royalty: vec![(accounts(2), 1_000)].into_iter().collect(),
+			tokens,
+			approved_market_id: Some(accounts(3)),
+		}
+	}
+
+	#[test]
+	fn upgrades_old_token_series() {
+		testing_env!(VMContextBuilder::new().build());
+		let fixture = old_series(1, "cat").try_to_vec().unwrap();
+
+		let token_series = TokenSeriesV1::try_from_slice(&fixture).unwrap().upgrade(1);
+		assert_eq!(token_series.metadata.title, Some("cat".to_string()));
+		assert_eq!(token_series.owner_id, accounts(1));
+		assert_eq!(token_series.royalty.get(&accounts(2)), Some(&1_000));
+		assert_eq!(token_series.approved_market_id, Some(accounts(3)));
+		assert_eq!(token_series.tokens.len(), 2);
+		assert_eq!(token_series.num_minted, 2);
+		assert!(!token_series.metadata_frozen);
+	}
+
+	#[test]
+	fn versioned_token_series_round_trips() {
+		testing_env!(VMContextBuilder::new().build());
+		let bytes = VersionedTokenSeries::from(old_series(1, "cat").upgrade(1)).try_to_vec().unwrap();
+		assert_eq!(bytes[0], TOKEN_SERIES_VERSION);
+
+		let token_series = VersionedTokenSeries::try_from_slice(&bytes).unwrap();
+		assert_eq!(token_series.owner_id, accounts(1));
+		assert_eq!(token_series.num_minted, 2);
+	}
+
+	#[test]
+	fn rejects_untagged_token_series() {
+		testing_env!(VMContextBuilder::new().build());
+		let fixture = old_series(1, "cat").try_to_vec().unwrap();
+		assert!(VersionedTokenSeries::try_from_slice(&fixture).is_err());
+	}
+
+	#[test]
+	fn migrates_old_state() {
+		testing_env!(VMContextBuilder::new()
+			.current_account_id(accounts(0))
+			.predecessor_account_id(accounts(0))
+			.build());
+		let mut old_state = ContractV1 {
+			tokens: NonFungibleToken::new(
+				StorageKey::NonFungibleToken,
+				accounts(0),
+				Some(StorageKey::TokenMetadata),
+				Some(StorageKey::Enumeration),
+				Some(StorageKey::Approval),
+			),
+			metadata: LazyOption::new(StorageKey::Metadata, None),
+			token_series_by_title: LookupMap::new(StorageKey::TokenSeriesByTitle),
+			token_series_by_id: UnorderedMap::new(StorageKey::TokenSeriesById),
+		};
+		for (token_series_id, title) in vec![(1, "cat"), (2, "dog")] {
+			old_state.token_series_by_title.insert(&title.to_string(), &token_series_id);
+			old_state.token_series_by_id.insert(&token_series_id, &old_series(token_series_id, title));
+		}
+		env::state_write(&old_state);
+
+		let contract = Contract::migrate();
+		assert_eq!(state_version(), STATE_VERSION);
+		assert_eq!(contract.next_series_id, 3);
+		assert_eq!(contract.token_series_by_id.len(), 2);
+		assert_eq!(contract.nft_get_series_json("dog".to_string()).token_series_id, U64(2));
+		assert_eq!(contract.nft_get_series_json("cat".to_string()).owner_id, accounts(1));
+		assert_eq!(contract.nft_series_supply_for_owner(accounts(1)), U64(2));
+		assert_eq!(contract.nft_supply_for_series("cat".to_string()), U64(2));
+	}
+	#[test]
+	fn migrate_keeps_current_state() {
+		testing_env!(VMContextBuilder::new()
+			.current_account_id(accounts(0))
+			.predecessor_account_id(accounts(0))
+			.attached_deposit(10u128.pow(24))
+			.build());
+		let mut contract = Contract::new_default_meta(accounts(0));
+		contract.nft_create_series(old_metadata("cat"), HashMap::new(), None);
+		env::state_write(&contract);
+
+		let contract = Contract::migrate();
+		assert_eq!(state_version(), STATE_VERSION);
+		assert_eq!(contract.next_series_id, 2);
+		assert_eq!(contract.nft_get_series_json("cat".to_string()).owner_id, accounts(0));
+
+		// running it twice changes nothing
+		env::state_write(&contract);
+		let contract = Contract::migrate();
+		assert_eq!(contract.token_series_by_id.len(), 1);
+	}
+}
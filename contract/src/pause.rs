@@ -0,0 +1,72 @@
+use crate::*;
+
+/// parts of the contract that can be halted independently, views and approval revokes are never paused
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum PauseFeature {
+	Mint,
+	Transfer,
+	Payout,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Paused {
+	mint: bool,
+	transfer: bool,
+	payout: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+	/// only owner or admin
+	#[payable]
+	pub fn pause(&mut self, feature: PauseFeature) {
+		assert_one_yocto();
+		self.assert_owner_or_admin();
+		self.internal_set_paused(feature, true);
+	}
+
+	/// only owner or admin
+	#[payable]
+	pub fn unpause(&mut self, feature: PauseFeature) {
+		assert_one_yocto();
+		self.assert_owner_or_admin();
+		self.internal_set_paused(feature, false);
+	}
+
+	/// views
+
+	pub fn get_paused(&self) -> &Paused {
+		&self.paused
+	}
+}
+
+impl Contract {
+	fn internal_set_paused(&mut self, feature: PauseFeature, paused: bool) {
+		let flag = match feature {
+			PauseFeature::Mint => &mut self.paused.mint,
+			PauseFeature::Transfer => &mut self.paused.transfer,
+			PauseFeature::Payout => &mut self.paused.payout,
+		};
+		assert_ne!(*flag, paused, "already set");
+		*flag = paused;
+
+		let data = [ContractPauseData { feature: &feature }];
+		if paused {
+			NftSeriesEventKind::ContractPause(&data).emit();
+		} else {
+			NftSeriesEventKind::ContractUnpause(&data).emit();
+		}
+	}
+
+	pub(crate) fn assert_not_paused(&self, feature: PauseFeature) {
+		let (paused, name) = match feature {
+			PauseFeature::Mint => (self.paused.mint, "minting"),
+			PauseFeature::Transfer => (self.paused.transfer, "transfers"),
+			PauseFeature::Payout => (self.paused.payout, "payouts"),
+		};
+		assert!(!paused, "{} paused", name);
+	}
+}
@@ -0,0 +1,230 @@
+use crate::*;
+use near_sdk::is_promise_success;
+
+/// gas for each ft_transfer of primary sale proceeds
+const GAS_FOR_FT_TRANSFER: Gas = Gas(5_000_000_000_000);
+/// gas for ft_resolve_payout to record a failed ft_transfer
+const GAS_FOR_FT_RESOLVE_PAYOUT: Gas = Gas(5_000_000_000_000);
+
+/// primary sale of a series directly from this contract, no market involved
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PublicMint {
+	/// price in NEAR, None is not for sale in NEAR
+	price: Option<U128>,
+	/// prices in whitelisted fungible tokens
+	ft_prices: HashMap<AccountId, U128>,
+	/// split of the proceeds in basis points, None uses the series royalty
+	/// the series owner gets the remainder
+	pub(crate) split: Option<HashMap<AccountId, u32>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PublicMintArgs {
+	pub token_series_title: TokenSeriesTitle,
+}
+
+#[near_bindgen]
+impl Contract {
+	/// only owner or admin
+	pub fn add_ft_token_ids(&mut self, ft_token_ids: Vec<AccountId>) -> Vec<bool> {
+		self.assert_owner_or_admin();
+		ft_token_ids
+			.into_iter()
+			.map(|ft_token_id| self.ft_token_ids.insert(&ft_token_id))
+			.collect()
+	}
+
+	/// series owner sets or clears (None) the public mint prices
+	#[payable]
+	pub fn nft_set_series_public_mint(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+		public_mint: Option<PublicMint>,
+	) {
+		assert!(env::attached_deposit() > 0, "Requires attached deposit of at least 1 yoctoNEAR");
+		let initial_storage_usage = env::storage_usage();
+
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		assert_eq!(&env::predecessor_account_id(), &token_series.owner_id, "not series owner");
+
+		if let Some(public_mint) = public_mint.as_ref() {
+			for ft_token_id in public_mint.ft_prices.keys() {
+				assert!(
+					self.ft_token_ids.contains(ft_token_id),
+					"Token {} not supported by this contract",
+					ft_token_id
+				);
+			}
+			if let Some(split) = public_mint.split.as_ref() {
+				self.assert_valid_split(split);
+			}
+		}
+		token_series.public_mint = public_mint;
+		self.token_series_by_id.insert(&token_series_id, &token_series);
+
+		self.refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+	}
+
+	/// anyone can buy the next edition, attach the NEAR price plus storage
+	#[payable]
+	pub fn nft_public_mint(
+		&mut self,
+		token_series_title: TokenSeriesTitle,
+	) -> Token {
+		let initial_storage_usage = env::storage_usage();
+
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		let price = public_mint_price(&token_series).expect("not for sale in NEAR");
+
+		assert_public_phase(&token_series);
+
+		let receiver_id = env::predecessor_account_id();
+		let token = self.internal_mint_series(token_series_id, &mut token_series, receiver_id.clone(), &receiver_id, None);
+
+		self.refund_deposit_with_price(env::storage_usage() - initial_storage_usage, price);
+		pay_primary_sale(&token_series, price);
+
+		token
+	}
+
+	/// retries the FT proceeds owed to the caller after a failed ft_transfer, e.g. once registered on ft_token_id
+	#[payable]
+	pub fn ft_withdraw_payout(&mut self, ft_token_id: AccountId) -> Promise {
+		assert_one_yocto();
+		let receiver_id = env::predecessor_account_id();
+		let mut payouts = self.ft_payouts_owed.get(&receiver_id).expect("no payouts owed");
+		let amount = payouts.remove(&ft_token_id).expect("no payout owed in that token");
+		if payouts.is_empty() {
+			self.ft_payouts_owed.remove(&receiver_id);
+		} else {
+			self.ft_payouts_owed.insert(&receiver_id, &payouts);
+		}
+		ft_payout(ft_token_id, receiver_id, U128(amount))
+	}
+
+	/// self callback, keeps the amount of a failed ft_transfer for ft_withdraw_payout
+	/// the contract pays the storage, it's freed again on withdraw
+	#[private]
+	pub fn ft_resolve_payout(
+		&mut self,
+		ft_token_id: AccountId,
+		receiver_id: AccountId,
+		amount: U128,
+	) {
+		if is_promise_success() {
+			return;
+		}
+		let mut payouts = self.ft_payouts_owed.get(&receiver_id).unwrap_or_default();
+		*payouts.entry(ft_token_id).or_insert(0) += amount.0;
+		self.ft_payouts_owed.insert(&receiver_id, &payouts);
+	}
+
+	/// views
+
+	pub fn get_ft_payouts_owed(&self, account_id: AccountId) -> HashMap<AccountId, U128> {
+		self.ft_payouts_owed
+			.get(&account_id)
+			.unwrap_or_default()
+			.into_iter()
+			.map(|(ft_token_id, amount)| (ft_token_id, U128(amount)))
+			.collect()
+	}
+
+	pub fn supported_ft_token_ids(&self) -> Vec<AccountId> {
+		self.ft_token_ids.to_vec()
+	}
+
+	pub fn nft_series_public_mint(&self, token_series_title: TokenSeriesTitle) -> Option<PublicMint> {
+		self.token_series_by_id.get(&self.token_series_by_title.get(&token_series_title).expect("no series")).expect("no series").into_inner().public_mint
+	}
+}
+
+/// callbacks from FT Contracts
+
+trait FungibleTokenReceiver {
+	fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128>;
+}
+
+#[near_bindgen]
+impl FungibleTokenReceiver for Contract {
+	/// mints the next edition to sender_id, storage comes from sender_id's storage balance
+	/// returns any amount over the price
+	fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+		let PublicMintArgs { token_series_title } =
+			near_sdk::serde_json::from_str(&msg).expect("Invalid PublicMintArgs");
+
+		let ft_token_id = env::predecessor_account_id();
+		assert!(self.ft_token_ids.contains(&ft_token_id), "Token {} not supported by this contract", ft_token_id);
+
+		let token_series_id = self.token_series_by_title.get(&token_series_title).expect("no series");
+		let mut token_series = self.token_series_by_id.get(&token_series_id).expect("no token");
+		let price = *token_series.public_mint
+			.as_ref()
+			.and_then(|public_mint| public_mint.ft_prices.get(&ft_token_id))
+			.expect("Not for sale in that token type");
+		assert!(amount.0 >= price.0, "Paid {} less than price {}", amount.0, price.0);
+		assert_public_phase(&token_series);
+
+		let initial_storage_usage = env::storage_usage();
+		self.internal_mint_series(token_series_id, &mut token_series, sender_id.clone(), &sender_id, None);
+		self.use_storage_balance(&sender_id, env::storage_usage() - initial_storage_usage);
+
+		for (receiver_id, amount) in primary_sale_payout(&token_series, price.0).payout {
+			if amount.0 > 0 {
+				ft_payout(ft_token_id.clone(), receiver_id, amount);
+			}
+		}
+
+		PromiseOrValue::Value(U128(amount.0 - price.0))
+	}
+}
+
+/// NEAR price of the series, None is not for sale in NEAR
+pub(crate) fn public_mint_price(token_series: &TokenSeries) -> Option<Balance> {
+	token_series.public_mint
+		.as_ref()
+		.and_then(|public_mint| public_mint.price)
+		.map(|price| price.0)
+}
+
+/// ft_transfer of amount to receiver_id, a failure is kept for ft_withdraw_payout
+fn ft_payout(ft_token_id: AccountId, receiver_id: AccountId, amount: U128) -> Promise {
+	ext_ft::ft_transfer(
+		receiver_id.clone(),
+		amount,
+		None,
+		ft_token_id.clone(),
+		1,
+		GAS_FOR_FT_TRANSFER,
+	)
+	.then(ext_self::ft_resolve_payout(
+		ft_token_id,
+		receiver_id,
+		amount,
+		env::current_account_id(),
+		NO_DEPOSIT,
+		GAS_FOR_FT_RESOLVE_PAYOUT,
+	))
+}
+
+/// transfers NEAR proceeds of a primary sale
+pub(crate) fn pay_primary_sale(token_series: &TokenSeries, price: Balance) {
+	for (receiver_id, amount) in primary_sale_payout(token_series, price).payout {
+		if amount.0 > 0 {
+			Promise::new(receiver_id).transfer(amount.0);
+		}
+	}
+}
+
+/// primary sale proceeds, split (or the series royalty) with the remainder to the series owner
+fn primary_sale_payout(token_series: &TokenSeries, price: Balance) -> Payout {
+	let split = token_series.public_mint
+		.as_ref()
+		.and_then(|public_mint| public_mint.split.as_ref())
+		.unwrap_or(&token_series.royalty);
+	royalty_payout(split, &token_series.owner_id, price)
+}
@@ -0,0 +1,103 @@
+use crate::*;
+
+/// roles granted by the contract owner or an admin, the owner (tokens.owner_id) has every role
+#[derive(Serialize, Deserialize, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+	Admin,
+	SeriesCreator,
+}
+
+#[near_bindgen]
+impl Contract {
+	/// step 1 of handing the contract to another account, replaces any pending proposal
+	#[payable]
+	pub fn propose_owner(&mut self, new_owner_id: AccountId) {
+		assert_one_yocto();
+		self.assert_owner();
+		self.pending_owner_id = Some(new_owner_id);
+	}
+
+	/// step 2, the proposed owner accepts
+	#[payable]
+	pub fn accept_owner(&mut self) {
+		assert_one_yocto();
+		let new_owner_id = env::predecessor_account_id();
+		assert_eq!(Some(&new_owner_id), self.pending_owner_id.as_ref(), "not pending owner");
+		self.tokens.owner_id = new_owner_id;
+		self.pending_owner_id = None;
+	}
+
+	#[payable]
+	pub fn grant_role(&mut self, account_id: AccountId, role: Role) -> bool {
+		assert_one_yocto();
+		self.assert_owner_or_admin();
+		match role {
+			Role::Admin => self.admins.insert(&account_id),
+			Role::SeriesCreator => self.series_creators.insert(&account_id),
+		}
+	}
+
+	#[payable]
+	pub fn revoke_role(&mut self, account_id: AccountId, role: Role) -> bool {
+		assert_one_yocto();
+		self.assert_owner_or_admin();
+		match role {
+			Role::Admin => self.admins.remove(&account_id),
+			Role::SeriesCreator => self.series_creators.remove(&account_id),
+		}
+	}
+
+	/// when true only accounts with the SeriesCreator role (or admins) can call nft_create_series
+	#[payable]
+	pub fn set_series_creators_only(&mut self, series_creators_only: bool) {
+		assert_one_yocto();
+		self.assert_owner_or_admin();
+		self.series_creators_only = series_creators_only;
+	}
+
+	/// views
+
+	pub fn get_owner(&self) -> AccountId {
+		self.tokens.owner_id.clone()
+	}
+
+	pub fn get_pending_owner(&self) -> Option<AccountId> {
+		self.pending_owner_id.clone()
+	}
+
+	pub fn has_role(&self, account_id: AccountId, role: Role) -> bool {
+		account_id == self.tokens.owner_id || match role {
+			Role::Admin => self.admins.contains(&account_id),
+			Role::SeriesCreator => self.series_creators.contains(&account_id) || self.admins.contains(&account_id),
+		}
+	}
+
+	pub fn get_role_members(&self, role: Role, from_index: Option<U128>, limit: Option<u64>) -> Vec<AccountId> {
+		match role {
+			Role::Admin => unordered_set_pagination(&self.admins, from_index, limit),
+			Role::SeriesCreator => unordered_set_pagination(&self.series_creators, from_index, limit),
+		}
+	}
+
+	pub fn get_series_creators_only(&self) -> bool {
+		self.series_creators_only
+	}
+}
+
+impl Contract {
+	pub(crate) fn assert_owner(&self) {
+		assert_eq!(env::predecessor_account_id(), self.tokens.owner_id, "Unauthorized");
+	}
+
+	pub(crate) fn assert_owner_or_admin(&self) {
+		assert!(self.has_role(env::predecessor_account_id(), Role::Admin), "Unauthorized");
+	}
+
+	pub(crate) fn assert_series_creator(&self) {
+		if self.series_creators_only {
+			assert!(self.has_role(env::predecessor_account_id(), Role::SeriesCreator), "not series creator");
+		}
+	}
+}
@@ -0,0 +1,174 @@
+use crate::*;
+use near_contract_standards::storage_management::{StorageBalance, StorageBalanceBounds, StorageManagement};
+
+/// storage_deposits entry: prefix, longest account id, balance and the per record overhead
+const STORAGE_REGISTRATION_BYTES: u64 = 1 + 4 + 64 + 16 + 40;
+
+/// NEP-145, the registration cost is kept and the rest is available for mints, series and approvals
+#[near_bindgen]
+impl StorageManagement for Contract {
+	#[payable]
+	fn storage_deposit(
+		&mut self,
+		account_id: Option<AccountId>,
+		registration_only: Option<bool>,
+	) -> StorageBalance {
+		let amount = env::attached_deposit();
+		let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+		let registration_only = registration_only.unwrap_or(false);
+
+		let balance = if let Some(balance) = self.storage_deposits.get(&account_id) {
+			if registration_only {
+				refund(amount);
+				balance
+			} else {
+				balance + amount
+			}
+		} else {
+			let min = self.storage_balance_bounds().min.0;
+			assert!(amount >= min, "Requires minimum deposit of {}", min);
+			if registration_only {
+				refund(amount - min);
+				0
+			} else {
+				amount - min
+			}
+		};
+		self.storage_deposits.insert(&account_id, &balance);
+
+		self.storage_balance_of(account_id).unwrap()
+	}
+
+	#[payable]
+	fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+		assert_one_yocto();
+		let account_id = env::predecessor_account_id();
+		let balance = self.storage_deposits.get(&account_id).expect("not registered");
+		let amount = amount.map(|amount| amount.0).unwrap_or(balance);
+		assert!(amount <= balance, "exceeds available storage balance of {}", balance);
+
+		self.storage_deposits.insert(&account_id, &(balance - amount));
+		refund(amount);
+
+		self.storage_balance_of(account_id).unwrap()
+	}
+
+	/// force burns the account's tokens and deletes its series with nothing minted
+	/// series with minted editions stay, their storage backs the editions of other holders
+	#[payable]
+	fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+		assert_one_yocto();
+		let account_id = env::predecessor_account_id();
+		if !self.storage_deposits.contains_key(&account_id) {
+			return false;
+		}
+
+		let token_ids = self.tokens.tokens_per_owner
+			.as_ref()
+			.and_then(|tokens_per_owner| tokens_per_owner.get(&account_id))
+			.map(|token_ids| token_ids.to_vec())
+			.unwrap_or_default();
+		let token_series_ids = self.series_by_owner
+			.get(&account_id)
+			.map(|series_set| series_set.to_vec())
+			.unwrap_or_default();
+		if !force.unwrap_or(false) {
+			assert!(
+				token_ids.is_empty() && token_series_ids.is_empty(),
+				"Can't unregister the account with tokens or series, use force to release them"
+			);
+		}
+
+		for token_id in token_ids {
+			self.internal_burn(&account_id, &token_id);
+		}
+		for token_series_id in token_series_ids {
+			let token_series = self.token_series_by_id.get(&token_series_id).expect("no series");
+			if token_series.num_minted == 0 {
+				let token_series_title = token_series.metadata.title.clone().expect("no title");
+				self.internal_delete_series(&account_id, token_series_id, &token_series_title);
+			}
+		}
+
+		// released storage was credited to the balance
+		let balance = self.storage_deposits.remove(&account_id).unwrap();
+		refund(balance + self.storage_balance_bounds().min.0);
+		true
+	}
+
+	fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+		StorageBalanceBounds {
+			min: U128(env::storage_byte_cost() * Balance::from(STORAGE_REGISTRATION_BYTES)),
+			max: None,
+		}
+	}
+
+	fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+		self.storage_deposits.get(&account_id).map(|balance| StorageBalance {
+			total: U128(balance + self.storage_balance_bounds().min.0),
+			available: U128(balance),
+		})
+	}
+}
+
+impl Contract {
+	/// storage_used bytes are paid by the attached deposit and any shortfall by the caller's storage balance
+	pub(crate) fn refund_deposit(&mut self, storage_used: u64) {
+		self.refund_deposit_with_price(storage_used, 0)
+	}
+
+	/// like refund_deposit when the attached deposit also pays price, price never comes from the storage balance
+	pub(crate) fn refund_deposit_with_price(&mut self, storage_used: u64, price: Balance) {
+		let storage_cost = env::storage_byte_cost() * Balance::from(storage_used);
+		let attached_deposit = env::attached_deposit();
+		let account_id = env::predecessor_account_id();
+		let balance = self.storage_deposits.get(&account_id).unwrap_or(0);
+
+		let required_deposit = price + storage_cost.saturating_sub(balance);
+		assert!(
+			required_deposit <= attached_deposit,
+			"Must attach {} yoctoNEAR to cover price and storage",
+			required_deposit,
+		);
+
+		let deposit = attached_deposit - price;
+		if deposit >= storage_cost {
+			refund(deposit - storage_cost);
+		} else {
+			self.storage_deposits.insert(&account_id, &(balance - (storage_cost - deposit)));
+		}
+	}
+
+	/// storage_freed bytes go back to account_id's storage balance, or as a transfer when not registered
+	pub(crate) fn refund_storage(&mut self, account_id: &AccountId, storage_freed: u64) {
+		let refund = env::storage_byte_cost() * Balance::from(storage_freed);
+		if let Some(balance) = self.storage_deposits.get(account_id) {
+			self.storage_deposits.insert(account_id, &(balance + refund));
+		} else if refund > 1 {
+			Promise::new(account_id.clone()).transfer(refund);
+		}
+	}
+
+	/// storage_used bytes are paid by account_id's storage balance alone
+	pub(crate) fn use_storage_balance(&mut self, account_id: &AccountId, storage_used: u64) {
+		if storage_used == 0 {
+			return;
+		}
+		let storage_cost = env::storage_byte_cost() * Balance::from(storage_used);
+		let balance = self.storage_deposits.get(account_id).unwrap_or(0);
+		assert!(
+			storage_cost <= balance,
+			"Storage balance of {} must cover {} yoctoNEAR, see storage_deposit",
+			account_id,
+			storage_cost,
+		);
+		self.storage_deposits.insert(account_id, &(balance - storage_cost));
+	}
+}
+
+/// sends amount back to the caller
+fn refund(amount: Balance) {
+	if amount > 1 {
+		Promise::new(env::predecessor_account_id()).transfer(amount);
+	}
+}
@@ -0,0 +1,138 @@
+// kept identical to market/src/upgrade.rs apart from the event enum
+use crate::*;
+use near_sdk::json_types::Base58CryptoHash;
+
+/// gas kept back from deploy_upgrade for the deploy action, the rest is attached to migrate
+const GAS_FOR_DEPLOY_UPGRADE: Gas = Gas(30_000_000_000_000);
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct StagedUpgrade {
+	/// sha256 of the staged wasm
+	code_hash: CryptoHash,
+	/// block timestamp (nanoseconds) deploy_upgrade is allowed from
+	deployable_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StagedUpgradeJson {
+	code_hash: Base58CryptoHash,
+	deployable_at: U64,
+}
+
+/// a shorter upgrade timelock, applied once the current one has passed
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingUpgradeTimelock {
+	upgrade_timelock: Option<U64>,
+	/// block timestamp (nanoseconds) upgrade_timelock replaces the current timelock
+	effective_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+	/// only owner, nanoseconds between stage_upgrade and deploy_upgrade, None can deploy right away
+	/// a longer timelock applies right away, a shorter one once the current timelock has passed
+	#[payable]
+	pub fn set_upgrade_timelock(&mut self, upgrade_timelock: Option<U64>) {
+		assert_one_yocto();
+		self.assert_owner();
+		let current = self.internal_upgrade_timelock();
+		if upgrade_timelock.map(|v| v.0).unwrap_or(0) >= current.unwrap_or(0) {
+			self.upgrade_timelock = upgrade_timelock.map(|v| v.0);
+			self.pending_upgrade_timelock = None;
+		} else {
+			self.upgrade_timelock = current;
+			self.pending_upgrade_timelock = Some(PendingUpgradeTimelock {
+				upgrade_timelock,
+				effective_at: U64(env::block_timestamp() + current.unwrap_or(0)),
+			});
+		}
+	}
+
+	/// only owner, replaces any staged code and restarts the timelock
+	#[payable]
+	pub fn stage_upgrade(&mut self, code: Base64VecU8) {
+		assert_one_yocto();
+		self.assert_owner();
+		let code: Vec<u8> = code.into();
+		let mut code_hash = CryptoHash::default();
+		code_hash.copy_from_slice(&env::sha256(&code));
+		let deployable_at = env::block_timestamp() + self.internal_upgrade_timelock().unwrap_or(0);
+
+		self.staged_code.set(&code);
+		self.staged_upgrade = Some(StagedUpgrade { code_hash, deployable_at });
+
+		NftSeriesEventKind::UpgradeStage(&[UpgradeStageData {
+			code_hash: Base58CryptoHash::from(code_hash),
+			deployable_at: U64(deployable_at),
+		}]).emit();
+	}
+
+	/// only owner, deploys the staged code and calls migrate on it in the same batch
+	/// the staged code is kept until migrate succeeds, if it fails the whole batch reverts and can be retried
+	#[payable]
+	pub fn deploy_upgrade(&mut self) -> Promise {
+		assert_one_yocto();
+		self.assert_owner();
+		let deployable_at = self.staged_upgrade.as_ref().expect("no staged upgrade").deployable_at;
+		assert!(env::block_timestamp() >= deployable_at, "upgrade timelocked until {}", deployable_at);
+		let gas_left = env::prepaid_gas() - env::used_gas();
+		assert!(
+			gas_left > GAS_FOR_DEPLOY_UPGRADE,
+			"deploy_upgrade requires more than {} gas left for migrate",
+			GAS_FOR_DEPLOY_UPGRADE.0
+		);
+		let code = self.staged_code.get().expect("no staged upgrade");
+
+		Promise::new(env::current_account_id())
+			.deploy_contract(code)
+			.function_call(
+				"migrate".to_string(),
+				Vec::new(),
+				NO_DEPOSIT,
+				gas_left - GAS_FOR_DEPLOY_UPGRADE,
+			)
+	}
+
+	/// views
+
+	pub fn get_staged_upgrade(&self) -> Option<StagedUpgradeJson> {
+		self.staged_upgrade.as_ref().map(|StagedUpgrade { code_hash, deployable_at }| StagedUpgradeJson {
+			code_hash: Base58CryptoHash::from(*code_hash),
+			deployable_at: U64(*deployable_at),
+		})
+	}
+
+	pub fn get_upgrade_timelock(&self) -> Option<U64> {
+		self.internal_upgrade_timelock().map(U64)
+	}
+
+	/// a shorter timelock waiting for the current one to pass
+	pub fn get_pending_upgrade_timelock(&self) -> Option<PendingUpgradeTimelock> {
+		self.pending_upgrade_timelock
+			.clone()
+			.filter(|pending| env::block_timestamp() < pending.effective_at.0)
+	}
+}
+
+impl Contract {
+	/// the pending timelock once it's effective, otherwise the current one
+	fn internal_upgrade_timelock(&self) -> Option<u64> {
+		match self.pending_upgrade_timelock.as_ref() {
+			Some(pending) if env::block_timestamp() >= pending.effective_at.0 => pending.upgrade_timelock.map(|v| v.0),
+			_ => self.upgrade_timelock,
+		}
+	}
+
+	/// called by migrate on the new code, so a staged upgrade is only cleared once it's deployed
+	pub(crate) fn internal_finish_upgrade(&mut self) {
+		if let Some(StagedUpgrade { code_hash, .. }) = self.staged_upgrade.take() {
+			self.staged_code.remove();
+
+			NftSeriesEventKind::UpgradeDeploy(&[UpgradeDeployData {
+				code_hash: Base58CryptoHash::from(code_hash),
+			}]).emit();
+		}
+	}
+}
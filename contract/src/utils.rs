@@ -1,5 +1,11 @@
 use crate::*;
 
+pub(crate) fn hash_account_id(account_id: &AccountId) -> CryptoHash {
+	let mut hash = CryptoHash::default();
+	hash.copy_from_slice(&env::sha256(account_id.as_bytes()));
+	hash
+}
+
 pub(crate) fn paginate<V>(
 	values: &Vector<V>,
 	from_index: Option<U128>,
@@ -32,13 +38,13 @@ pub(crate) fn unordered_map_val_pagination<K, V>(
 	paginate(map.values_as_vector(), from_index, limit)
 }
 
-// pub(crate) fn unordered_map_key_pagination<K, V>(
-//     map: &UnorderedMap<K, V>,
-//     from_index: Option<U128>,
-//     limit: Option<u64>,
-// ) -> Vec<K> where K: BorshSerialize + BorshDeserialize, V: BorshSerialize + BorshDeserialize {
-// 	paginate(map.keys_as_vector(), from_index, limit)
-// }
+pub(crate) fn unordered_map_key_pagination<K, V>(
+    map: &UnorderedMap<K, V>,
+    from_index: Option<U128>,
+    limit: Option<u64>,
+) -> Vec<K> where K: BorshSerialize + BorshDeserialize, V: BorshSerialize + BorshDeserialize {
+	paginate(map.keys_as_vector(), from_index, limit)
+}
 
 pub(crate) fn unordered_set_pagination<V>(
     set: &UnorderedSet<V>,
@@ -48,19 +54,129 @@ pub(crate) fn unordered_set_pagination<V>(
 	paginate(set.as_vector(), from_index, limit)
 }
 
-pub(crate) fn refund_deposit(storage_used: u64) {
-    let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
-    let attached_deposit = env::attached_deposit();
+/// e.g. "42:2" -> 42
+pub(crate) fn token_series_id_from_token_id(token_id: &TokenId) -> TokenSeriesId {
+	token_id
+		.split(TOKEN_DELIMETER)
+		.next()
+		.unwrap()
+		.parse()
+		.expect("invalid token_id")
+}
+
+/// floor(balance * royalty / ROYALTY_BASIS_POINTS) without overflowing u128
+pub(crate) fn royalty_to_payout(royalty: u32, balance: Balance) -> Balance {
+	let royalty = royalty as u128;
+	let basis_points = ROYALTY_BASIS_POINTS as u128;
+	balance / basis_points * royalty + balance % basis_points * royalty / basis_points
+}
+
+/// the predecessor is the authorized_id when it is not the owner
+pub(crate) fn log_nft_transfer(
+	old_owner_id: &AccountId,
+	new_owner_id: &AccountId,
+	token_id: &TokenId,
+	memo: Option<&str>,
+) {
+	let predecessor_id = env::predecessor_account_id();
+	Nep171EventKind::NftTransfer(&[NftTransferData {
+		authorized_id: Some(&predecessor_id).filter(|predecessor_id| *predecessor_id != old_owner_id),
+		old_owner_id,
+		new_owner_id,
+		token_ids: vec![token_id],
+		memo,
+	}]).emit();
+}
+
+pub(crate) fn log_nft_mint(owner_id: &AccountId, token_ids: &[TokenId]) {
+	Nep171EventKind::NftMint(&[NftMintData {
+		owner_id,
+		token_ids: token_ids.iter().map(|token_id| token_id.as_str()).collect(),
+		memo: None,
+	}]).emit();
+}
+
+/// grant has not expired and has quota for num_to_mint editions
+pub(crate) fn grant_is_active(grant: &MinterGrant, num_to_mint: u64) -> bool {
+	grant.expires_at.map(|expires_at| env::block_timestamp() < expires_at.0).unwrap_or(true)
+		&& grant.quota.map(|quota| quota as u64 >= num_to_mint).unwrap_or(true)
+}
+
+/// predecessor is the series owner or a minter able to mint num_to_mint editions
+pub(crate) fn assert_series_minter(token_series: &TokenSeries, num_to_mint: u64) {
+	let minter_id = env::predecessor_account_id();
+	if minter_id == token_series.owner_id {
+		return;
+	}
+	let grant = token_series.minters.get(&minter_id).expect("not series owner or minter");
+	assert!(grant_is_active(grant, num_to_mint), "minter grant expired or quota exceeded");
+}
+
+/// counts minted editions against the predecessor's quota, no-op for the series owner
+pub(crate) fn use_minter_quota(token_series: &mut TokenSeries, minted: u64) {
+	let minter_id = env::predecessor_account_id();
+	if minter_id == token_series.owner_id {
+		return;
+	}
+	if let Some(MinterGrant { quota: Some(quota), .. }) = token_series.minters.get_mut(&minter_id) {
+		*quota -= minted as u32;
+	}
+}
+
+/// splits balance by royalty, owner_id gets the remainder so payouts always sum to balance
+pub(crate) fn royalty_payout(
+	royalty: &HashMap<AccountId, u32>,
+	owner_id: &AccountId,
+	balance: Balance,
+) -> Payout {
+	let mut total_royalty_payout = 0u128;
+	let mut payout_struct: Payout = Payout{
+		payout: HashMap::new()
+	};
+	for (k, v) in royalty.iter() {
+		// skip seller and payout once at end
+		if k != owner_id {
+			let amount = royalty_to_payout(*v, balance);
+			payout_struct.payout.insert(k.clone(), U128(amount));
+			total_royalty_payout += amount;
+		}
+	}
+	// payout to seller, gets any rounding remainder
+	let seller_payout = balance.checked_sub(total_royalty_payout).expect("royalty exceeds balance");
+	payout_struct.payout.insert(owner_id.clone(), U128(seller_payout));
+	payout_struct
+}
+
+/// panics outside of the series mint window
+pub(crate) fn assert_mint_window(token_series: &TokenSeries) {
+	let now = env::block_timestamp();
+	if let Some(mint_starts_at) = token_series.mint_starts_at {
+		assert!(now >= mint_starts_at, "series minting not started");
+	}
+	if let Some(mint_ends_at) = token_series.mint_ends_at {
+		assert!(now < mint_ends_at, "series minting ended");
+	}
+}
 
-    assert!(
-        required_cost <= attached_deposit,
-        "Must attach {} yoctoNEAR to cover storage",
-        required_cost,
-    );
+/// metadata.copies, or the editions minted once the mint window has ended
+pub(crate) fn series_copies(token_series: &TokenSeries) -> Option<u64> {
+	match token_series.mint_ends_at {
+		Some(mint_ends_at) if env::block_timestamp() >= mint_ends_at => Some(token_series.num_minted),
+		_ => token_series.metadata.copies,
+	}
+}
 
-    let refund = attached_deposit - required_cost;
-	// log!("refund_deposit amount {}", refund);
-    if refund > 1 {
-        Promise::new(env::predecessor_account_id()).transfer(refund);
-    }
+pub(crate) fn series_json(token_series_id: TokenSeriesId, token_series: TokenSeries) -> TokenSeriesJson {
+	let mut metadata = token_series.metadata.clone();
+	metadata.copies = series_copies(&token_series);
+	TokenSeriesJson{
+		token_series_id: token_series_id.into(),
+		metadata,
+		owner_id: token_series.owner_id,
+		royalty: token_series.royalty,
+		metadata_frozen: token_series.metadata_frozen,
+		mint_starts_at: token_series.mint_starts_at.map(U64),
+		mint_ends_at: token_series.mint_ends_at.map(U64),
+		transferable: token_series.transferable,
+	}
 }
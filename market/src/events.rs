@@ -0,0 +1,56 @@
+use crate::*;
+use near_sdk::json_types::Base58CryptoHash;
+
+const EVENT_JSON: &str = "EVENT_JSON:";
+const NFT_MARKET_STANDARD: &str = "nft_market";
+const NFT_MARKET_VERSION: &str = "1.0.0";
+
+/// NEP-297 event, logged as EVENT_JSON:{"standard":..,"version":..,"event":..,"data":[..]}
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct NftMarketEvent<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event_kind: NftMarketEventKind<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+pub enum NftMarketEventKind<'a> {
+    MarketPause(&'a [MarketPauseData]),
+    MarketUnpause(&'a [MarketPauseData]),
+    UpgradeStage(&'a [UpgradeStageData]),
+    UpgradeDeploy(&'a [UpgradeDeployData]),
+}
+
+impl NftMarketEventKind<'_> {
+    pub fn emit(self) {
+        let event = NftMarketEvent {
+            standard: NFT_MARKET_STANDARD,
+            version: NFT_MARKET_VERSION,
+            event_kind: self,
+        };
+        env::log_str(&format!("{}{}", EVENT_JSON, near_sdk::serde_json::to_string(&event).unwrap()));
+    }
+}
+
+/// the market has a single switch, so there is nothing to log but the event
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MarketPauseData {}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UpgradeStageData {
+    pub code_hash: Base58CryptoHash,
+    pub deployable_at: U64,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UpgradeDeployData {
+    pub code_hash: Base58CryptoHash,
+}
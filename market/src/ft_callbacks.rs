@@ -9,6 +9,7 @@ trait FungibleTokenReceiver {
 #[near_bindgen]
 impl FungibleTokenReceiver for Contract {
     fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+        self.assert_not_paused();
         let PurchaseArgs {
             nft_contract_id,
             token_id,
@@ -32,7 +33,7 @@ impl FungibleTokenReceiver for Contract {
 
         if amount == price {
             self.process_purchase(
-                sale,
+                sale.into_inner(),
                 nft_contract_id.into(),
                 token_id,
                 ft_token_id,
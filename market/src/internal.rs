@@ -15,13 +15,29 @@ impl Contract {
         );
     }
 
+    pub(crate) fn assert_not_paused(&self) {
+        assert!(!self.paused, "market paused");
+    }
+
+    pub(crate) fn internal_set_paused(&mut self, paused: bool) {
+        assert_ne!(self.paused, paused, "already set");
+        self.paused = paused;
+
+        let data = [MarketPauseData {}];
+        if paused {
+            NftMarketEventKind::MarketPause(&data).emit();
+        } else {
+            NftMarketEventKind::MarketUnpause(&data).emit();
+        }
+    }
+
     pub(crate) fn internal_remove_sale(
         &mut self,
         nft_contract_id: AccountId,
         token_id: TokenId,
     ) -> Sale {
         let contract_and_token_id = format!("{}{}{}", &nft_contract_id, DELIMETER, token_id);
-        let sale = self.sales.remove(&contract_and_token_id).expect("No sale");
+        let sale = self.sales.remove(&contract_and_token_id).expect("No sale").into_inner();
 
         let mut by_owner_id = self.by_owner_id.get(&sale.owner_id).expect("No sale by_owner_id");
         by_owner_id.remove(&contract_and_token_id);
@@ -1,6 +1,6 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LookupMap, UnorderedMap, UnorderedSet};
-use near_sdk::json_types::{U128, U64};
+use near_sdk::collections::{LazyOption, LookupMap, UnorderedMap, UnorderedSet};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
     assert_one_yocto, env, ext_contract, near_bindgen, AccountId, Balance, CryptoHash, Gas,
@@ -9,17 +9,23 @@ use near_sdk::{
 use std::cmp::min;
 use std::collections::HashMap;
 
+use crate::events::*;
 use crate::external::*;
 use crate::internal::*;
+use crate::migrate::*;
 use crate::sale::*;
+use crate::upgrade::*;
 use near_sdk::env::STORAGE_PRICE_PER_BYTE;
 
+mod events;
 mod external;
 mod ft_callbacks;
 mod internal;
+mod migrate;
 mod nft_callbacks;
 mod sale;
 mod sale_views;
+mod upgrade;
 
 // TODO check seller supports storage_deposit at ft_token_id they want to post sale in
 
@@ -46,12 +52,20 @@ pub struct Payout {
 pub struct Contract {
 	pub near_ft: AccountId,
     pub owner_id: AccountId,
-    pub sales: UnorderedMap<ContractAndTokenId, Sale>,
+    pub sales: UnorderedMap<ContractAndTokenId, VersionedSale>,
     pub by_owner_id: LookupMap<AccountId, UnorderedSet<ContractAndTokenId>>,
     pub by_nft_contract_id: LookupMap<AccountId, UnorderedSet<ContractAndTokenId>>,
     pub by_nft_token_type: LookupMap<TokenType, UnorderedSet<ContractAndTokenId>>,
     pub ft_token_ids: UnorderedSet<AccountId>,
     pub storage_deposits: LookupMap<AccountId, Balance>,
+    /// emergency switch, blocks new listings and purchases
+    pub paused: bool,
+    /// wasm waiting for deploy_upgrade, lazy so it is only read on deploy
+    pub staged_code: LazyOption<Vec<u8>>,
+    pub staged_upgrade: Option<StagedUpgrade>,
+    /// nanoseconds holders get to see a staged upgrade before it can be deployed
+    pub upgrade_timelock: Option<u64>,
+    pub pending_upgrade_timelock: Option<PendingUpgradeTimelock>,
 }
 
 /// Helper structure to for keys of the persistent collections.
@@ -66,12 +80,15 @@ pub enum StorageKey {
     ByNFTTokenTypeInner { token_type_hash: CryptoHash },
     FTTokenIds,
     StorageDeposits,
+    StagedCode,
+    StateVersion,
 }
 
 #[near_bindgen]
 impl Contract {
     #[init]
     pub fn new(owner_id: AccountId, ft_token_ids: Option<Vec<AccountId>>) -> Self {
+        write_state_version();
         let mut this = Self {
 			near_ft: AccountId::new_unchecked("near".to_string()),
             owner_id,
@@ -81,6 +98,11 @@ impl Contract {
             by_nft_token_type: LookupMap::new(StorageKey::ByNFTTokenType),
             ft_token_ids: UnorderedSet::new(StorageKey::FTTokenIds),
             storage_deposits: LookupMap::new(StorageKey::StorageDeposits),
+            paused: false,
+            staged_code: LazyOption::new(StorageKey::StagedCode, None),
+            staged_upgrade: None,
+            upgrade_timelock: None,
+            pending_upgrade_timelock: None,
         };
         // support NEAR by default
         this.ft_token_ids.insert(&AccountId::new_unchecked("near".to_string()));
@@ -103,6 +125,22 @@ impl Contract {
             .collect()
     }
 
+    /// only owner, storage_withdraw and remove_sale keep working while paused
+    #[payable]
+    pub fn pause(&mut self) {
+        assert_one_yocto();
+        self.assert_owner();
+        self.internal_set_paused(true);
+    }
+
+    /// only owner
+    #[payable]
+    pub fn unpause(&mut self) {
+        assert_one_yocto();
+        self.assert_owner();
+        self.internal_set_paused(false);
+    }
+
     /// TODO remove token (should check if sales can complete even if owner stops supporting token type)
 
     #[payable]
@@ -149,4 +187,8 @@ impl Contract {
     pub fn storage_paid(&self, account_id: AccountId) -> U128 {
         U128(self.storage_deposits.get(&account_id).unwrap_or(0))
     }
+
+    pub fn get_paused(&self) -> bool {
+        self.paused
+    }
 }
@@ -0,0 +1,230 @@
+use crate::*;
+use std::io;
+use std::ops::{Deref, DerefMut};
+
+/// written in front of every stored Sale, the untagged sales of ContractV1 are version 1
+/// bump when a deployed Sale layout changes and upgrade the previous layout in deserialize
+const SALE_VERSION: u8 = 2;
+/// written under StorageKey::StateVersion, ContractV1 never wrote it and is version 1
+/// bump when a deployed Contract layout changes and upgrade the previous layout in migrate
+const STATE_VERSION: u8 = 2;
+
+/// Sale as stored in sales
+pub struct VersionedSale(Sale);
+
+impl VersionedSale {
+    pub fn into_inner(self) -> Sale {
+        self.0
+    }
+}
+
+impl From<Sale> for VersionedSale {
+    fn from(sale: Sale) -> Self {
+        Self(sale)
+    }
+}
+
+impl Deref for VersionedSale {
+    type Target = Sale;
+    fn deref(&self) -> &Sale {
+        &self.0
+    }
+}
+
+impl DerefMut for VersionedSale {
+    fn deref_mut(&mut self) -> &mut Sale {
+        &mut self.0
+    }
+}
+
+impl BorshSerialize for VersionedSale {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        BorshSerialize::serialize(&SALE_VERSION, writer)?;
+        BorshSerialize::serialize(&self.0, writer)
+    }
+}
+
+impl BorshDeserialize for VersionedSale {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        match <u8 as BorshDeserialize>::deserialize(buf)? {
+            SALE_VERSION => Ok(Self(<Sale as BorshDeserialize>::deserialize(buf)?)),
+            version => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown Sale version {}", version),
+            )),
+        }
+    }
+}
+
+/// Contract layout before state versions
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ContractV1 {
+    pub near_ft: AccountId,
+    pub owner_id: AccountId,
+    /// same layout as Sale, without the version tag
+    pub sales: UnorderedMap<ContractAndTokenId, Sale>,
+    pub by_owner_id: LookupMap<AccountId, UnorderedSet<ContractAndTokenId>>,
+    pub by_nft_contract_id: LookupMap<AccountId, UnorderedSet<ContractAndTokenId>>,
+    pub by_nft_token_type: LookupMap<TokenType, UnorderedSet<ContractAndTokenId>>,
+    pub ft_token_ids: UnorderedSet<AccountId>,
+    pub storage_deposits: LookupMap<AccountId, Balance>,
+}
+
+/// kept outside of the Contract state so migrate knows the layout before reading it
+pub(crate) fn state_version() -> u8 {
+    LazyOption::<u8>::new(StorageKey::StateVersion, None).get().unwrap_or(1)
+}
+
+pub(crate) fn write_state_version() {
+    LazyOption::new(StorageKey::StateVersion, Some(&STATE_VERSION));
+}
+
+#[near_bindgen]
+impl Contract {
+    /// call right after deploying new code, a no-op when the state is already the current version
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let mut this: Self = match state_version() {
+            STATE_VERSION => env::state_read().expect("no state"),
+            1 => Self::migrate_v1(),
+            version => panic!("unknown state version {}", version),
+        };
+        write_state_version();
+        this.internal_finish_upgrade();
+
+        this
+    }
+}
+
+impl Contract {
+    /// rewrites every sale so gas grows with the number of sales
+    fn migrate_v1() -> Self {
+        let ContractV1 {
+            near_ft,
+            owner_id,
+            sales: mut old_sales,
+            by_owner_id,
+            by_nft_contract_id,
+            by_nft_token_type,
+            ft_token_ids,
+            storage_deposits,
+        } = env::state_read().expect("no state");
+
+        let mut this = Self {
+            near_ft,
+            owner_id,
+            sales: UnorderedMap::new(StorageKey::Sales),
+            by_owner_id,
+            by_nft_contract_id,
+            by_nft_token_type,
+            ft_token_ids,
+            storage_deposits,
+            paused: false,
+            staged_code: LazyOption::new(StorageKey::StagedCode, None),
+            staged_upgrade: None,
+            upgrade_timelock: None,
+            pending_upgrade_timelock: None,
+        };
+
+        // same storage prefix, clear the untagged records before writing them back tagged
+        let sales = old_sales.to_vec();
+        old_sales.clear();
+        for (contract_and_token_id, sale) in sales {
+            this.sales.insert(&contract_and_token_id, &sale.into());
+        }
+
+        this
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn old_sale(token_id: &str) -> Sale {
+        Sale {
+            owner_id: accounts(1),
+            approval_id: 3,
+            nft_contract_id: accounts(2),
+            token_id: token_id.to_string(),
+            conditions: vec![(AccountId::new_unchecked("near".to_string()), U128(1_000))].into_iter().collect(),
+            created_at: U64(42),
+            is_series: Some(true),
+            token_type: Some("cat".to_string()),
+            bids: None,
+        }
+    }
+
+    #[test]
+    fn versioned_sale_round_trips() {
+        testing_env!(VMContextBuilder::new().build());
+        let bytes = VersionedSale::from(old_sale("cat")).try_to_vec().unwrap();
+        assert_eq!(bytes[0], SALE_VERSION);
+
+        let sale = VersionedSale::try_from_slice(&bytes).unwrap();
+        assert_eq!(sale.owner_id, accounts(1));
+        assert_eq!(sale.approval_id, 3);
+        assert_eq!(sale.token_type, Some("cat".to_string()));
+    }
+
+    #[test]
+    fn rejects_untagged_sale() {
+        testing_env!(VMContextBuilder::new().build());
+        let fixture = old_sale("cat").try_to_vec().unwrap();
+        assert!(VersionedSale::try_from_slice(&fixture).is_err());
+    }
+
+    #[test]
+    fn migrates_old_state() {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .predecessor_account_id(accounts(0))
+            .build());
+        let mut old_state = ContractV1 {
+            near_ft: AccountId::new_unchecked("near".to_string()),
+            owner_id: accounts(0),
+            sales: UnorderedMap::new(StorageKey::Sales),
+            by_owner_id: LookupMap::new(StorageKey::ByOwnerId),
+            by_nft_contract_id: LookupMap::new(StorageKey::ByNFTContractId),
+            by_nft_token_type: LookupMap::new(StorageKey::ByNFTTokenType),
+            ft_token_ids: UnorderedSet::new(StorageKey::FTTokenIds),
+            storage_deposits: LookupMap::new(StorageKey::StorageDeposits),
+        };
+        for token_id in vec!["1:1", "1:2"] {
+            let contract_and_token_id = format!("{}{}{}", accounts(2), DELIMETER, token_id);
+            old_state.sales.insert(&contract_and_token_id, &old_sale(token_id));
+        }
+        old_state.storage_deposits.insert(&accounts(1), &(2 * STORAGE_PER_SALE));
+        env::state_write(&old_state);
+
+        let contract = Contract::migrate();
+        assert_eq!(state_version(), STATE_VERSION);
+        assert!(!contract.paused);
+        assert_eq!(contract.get_supply_sales(), U64(2));
+        let sale = contract.get_sale(format!("{}{}1:2", accounts(2), DELIMETER)).unwrap();
+        assert_eq!(sale.token_id, "1:2");
+        assert_eq!(sale.conditions.get(&contract.near_ft), Some(&U128(1_000)));
+        assert_eq!(contract.storage_paid(accounts(1)), U128(2 * STORAGE_PER_SALE));
+    }
+    #[test]
+    fn migrate_keeps_current_state() {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .predecessor_account_id(accounts(0))
+            .build());
+        let contract = Contract::new(accounts(0), Some(vec![accounts(4)]));
+        env::state_write(&contract);
+
+        let contract = Contract::migrate();
+        assert_eq!(state_version(), STATE_VERSION);
+        assert_eq!(contract.supported_ft_token_ids().len(), 2);
+
+        // running it twice changes nothing
+        env::state_write(&contract);
+        let contract = Contract::migrate();
+        assert_eq!(contract.owner_id, accounts(0));
+    }
+}
@@ -51,7 +51,7 @@ impl NonFungibleTokenApprovalsReceiver for Contract {
         let contract_and_token_id = format!("{}{}{}", nft_contract_id, DELIMETER, token_id);
         self.sales.insert(
             &contract_and_token_id,
-            &Sale {
+            &VersionedSale::from(Sale {
                 owner_id: owner_id.clone(),
                 created_at: env::block_timestamp().into(),
                 approval_id,
@@ -61,7 +61,7 @@ impl NonFungibleTokenApprovalsReceiver for Contract {
                 is_series: None,
                 token_type: Some(token_type.clone()),
                 bids: None,
-            },
+            }),
         );
 
         // extra for views
@@ -104,97 +104,107 @@ impl NonFungibleTokenApprovalsReceiver for Contract {
     }
 }
 
-// trait NonFungibleSeriesApprovalReceiver {
-//     fn series_on_approve(&mut self, series_name: String, owner_id: AccountId, msg: String);
-// }
-
-// #[near_bindgen]
-// impl NonFungibleSeriesApprovalReceiver for Contract {
-//     #[payable]
-//     fn series_on_approve(&mut self, series_name: String, owner_id: AccountId, msg: String) {
-//         self.check_valid_callback(owner_id.clone());
-
-//         let nft_contract_id = env::predecessor_account_id();
-
-//         let SaleArgs {
-//             sale_conditions,
-//             token_type: _,
-//         } = near_sdk::serde_json::from_str(&msg).expect("Not valid SaleArgs");
-
-//         let mut conditions = HashMap::new();
-//         for Price { price, ft_token_id } in sale_conditions {
-//             if !self.ft_token_ids.contains(&ft_token_id) {
-//                 env::panic_str(
-//                     &format!("Token {} not supported by this market", ft_token_id),
-//                 );
-//             }
-//             conditions.insert(ft_token_id.into(), price.unwrap_or(U128(0)));
-//         }
-
-//         // log!("add_sale for owner: {}", &owner_id);
-
-//         let contract_and_token_id = format!("{}{}{}", nft_contract_id, DELIMETER, series_name);
-//         self.sales.insert(
-//             &contract_and_token_id,
-//             &Sale {
-//                 owner_id: owner_id.clone().into(),
-//                 created_at: env::block_timestamp().into(),
-//                 approval_id: u64(0),
-//                 nft_contract_id: nft_contract_id.clone(),
-//                 token_id: series_name.clone(),
-//                 conditions,
-//                 is_series: Some(true),
-//                 token_type: None,
-//                 bids: None,
-//             },
-//         );
-
-//         // extra for views
-
-//         let mut by_owner_id = self.by_owner_id.get(&owner_id).unwrap_or_else(|| {
-//             UnorderedSet::new(
-//                 StorageKey::ByOwnerIdInner {
-//                     account_id_hash: hash_account_id(&owner_id),
-//                 }
-//             )
-//         });
-
-//         by_owner_id.insert(&contract_and_token_id);
-//         self.by_owner_id.insert(&owner_id, &by_owner_id);
-
-//         let mut by_nft_contract_id = self
-//             .by_nft_contract_id
-//             .get(&nft_contract_id)
-//             .unwrap_or_else(|| {
-//                 UnorderedSet::new(
-//                     StorageKey::ByNFTContractIdInner {
-//                         account_id_hash: hash_account_id(&nft_contract_id),
-//                     }
-//                 )
-//             });
-//         by_nft_contract_id.insert(&contract_and_token_id);
-//         self.by_nft_contract_id
-//             .insert(&nft_contract_id, &by_nft_contract_id);
-
-//         let mut by_nft_token_type = self.by_nft_token_type.get(&series_name).unwrap_or_else(|| {
-//             UnorderedSet::new(
-//                 StorageKey::ByNFTTokenTypeInner {
-//                     token_type_hash: hash_account_id(&AccountId::new_unchecked(series_name.clone())),
-//                 }
-//             )
-//         });
-//         by_nft_token_type.insert(&contract_and_token_id);
-//         self.by_nft_token_type
-//             .insert(&series_name, &by_nft_token_type);
-//     }
-
-// }
+trait NonFungibleSeriesApprovalReceiver {
+    fn series_on_approve(
+        &mut self,
+        token_series_title: String,
+        owner_id: AccountId,
+        approval_id: u64,
+        msg: String,
+    );
+}
+
+#[near_bindgen]
+impl NonFungibleSeriesApprovalReceiver for Contract {
+    /// lists the whole series for primary sale, editions are lazy minted on purchase
+    #[payable]
+    fn series_on_approve(
+        &mut self,
+        token_series_title: String,
+        owner_id: AccountId,
+        approval_id: u64,
+        msg: String,
+    ) {
+        let nft_contract_id = env::predecessor_account_id();
+
+        // a re-approval replaces the sale, possibly listed by a previous series owner
+        let contract_and_token_id = format!("{}{}{}", nft_contract_id, DELIMETER, token_series_title);
+        if self.sales.get(&contract_and_token_id).is_some() {
+            self.internal_remove_sale(nft_contract_id.clone(), token_series_title.clone());
+        }
+
+        self.check_valid_callback(owner_id.clone());
+
+        let SaleArgs { sale_conditions, token_type, is_auction: _ } =
+            near_sdk::serde_json::from_str(&msg).expect("Not valid SaleArgs");
+        assert_eq!(token_type, token_series_title, "TokenType should be the series title");
+
+        for (ft_token_id, _price) in sale_conditions.clone() {
+            if !self.ft_token_ids.contains(&ft_token_id) {
+                env::panic_str(
+                    &format!("Token {} not supported by this market", ft_token_id),
+                );
+            }
+        }
+
+        self.sales.insert(
+            &contract_and_token_id,
+            &VersionedSale::from(Sale {
+                owner_id: owner_id.clone(),
+                created_at: env::block_timestamp().into(),
+                approval_id,
+                nft_contract_id: nft_contract_id.clone(),
+                token_id: token_series_title,
+                conditions: sale_conditions,
+                is_series: Some(true),
+                token_type: Some(token_type.clone()),
+                bids: None,
+            }),
+        );
+
+        // extra for views
+
+        let mut by_owner_id = self.by_owner_id.get(&owner_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::ByOwnerIdInner {
+                account_id_hash: hash_account_id(&owner_id),
+            })
+        });
+        by_owner_id.insert(&contract_and_token_id);
+        self.by_owner_id.insert(&owner_id, &by_owner_id);
+
+        let mut by_nft_contract_id = self
+            .by_nft_contract_id
+            .get(&nft_contract_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::ByNFTContractIdInner {
+                    account_id_hash: hash_account_id(&nft_contract_id),
+                })
+            });
+        by_nft_contract_id.insert(&contract_and_token_id);
+        self.by_nft_contract_id
+            .insert(&nft_contract_id, &by_nft_contract_id);
+
+        // token_type is the series title, checked above
+        let mut by_nft_token_type =
+            self.by_nft_token_type.get(&token_type).unwrap_or_else(|| {
+                UnorderedSet::new(
+                    StorageKey::ByNFTTokenTypeInner {
+                        token_type_hash: hash_account_id(&AccountId::new_unchecked(token_type.clone())),
+                    }
+                )
+            });
+        by_nft_token_type.insert(&contract_and_token_id);
+        self.by_nft_token_type
+            .insert(&token_type, &by_nft_token_type);
+    }
+}
 
 #[near_bindgen]
 impl Contract {
 
     #[private]
     pub fn check_valid_callback(&mut self, owner_id: AccountId) {
+        self.assert_not_paused();
 
         // enforce cross contract calls and owner_id is signer
 
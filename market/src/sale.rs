@@ -95,6 +95,7 @@ impl Contract {
         token_id: String,
         msg: Option<String>,
     ) {
+        self.assert_not_paused();
         let contract_id: AccountId = nft_contract_id;
         let contract_and_token_id = format!("{}{}{}", contract_id, DELIMETER, token_id);
         let sale = self.sales.get(&contract_and_token_id).expect("No sale");
@@ -119,7 +120,7 @@ impl Contract {
                 assert!(diff > 0, "Attached deposit must be greater than price (to pay for storage of minted NFT).");
             }
             self.process_purchase(
-                sale,
+                sale.into_inner(),
                 contract_id,
                 token_id,
                 self.near_ft.clone(),
@@ -272,7 +273,7 @@ impl Contract {
             price: U128(amount),
         };
         let mut sale = self.sales.get(&contract_and_token_id).expect("No sale");
-        let mut bids = sale.bids.unwrap_or_default();
+        let mut bids = sale.bids.take().unwrap_or_default();
         let current_bid = bids.get(&ft_token_id);
         if let Some(current_bid) = current_bid {
             // refund current bid holder
@@ -299,18 +300,19 @@ impl Contract {
         ft_token_id: AccountId,
     ) {
         assert_one_yocto();
+        self.assert_not_paused();
 
         let contract_id: AccountId = nft_contract_id.into();
         let contract_and_token_id = format!("{}{}{}", contract_id, DELIMETER, token_id);
         // remove bid before proceeding to process purchase
         let mut sale = self.sales.get(&contract_and_token_id).expect("No sale");
-        let mut bids = sale.bids.unwrap_or_default();
+        let mut bids = sale.bids.take().unwrap_or_default();
         let bid = bids.remove(&ft_token_id).expect("No bid");
         sale.bids = Some(bids);
         self.sales.insert(&contract_and_token_id, &sale);
         // panics at `self.internal_remove_sale` and reverts above if predecessor is not sale.owner_id
         self.process_purchase(
-            sale,
+            sale.into_inner(),
             contract_id,
             token_id,
             ft_token_id.into(),
@@ -20,7 +20,7 @@ impl Contract {
         let start = u64::from(from_index);
         let end = min(start + u64::from(limit), self.sales.len());
         for i in start..end {
-            tmp.push(self.sales.get(&keys.get(i).unwrap()).unwrap());
+            tmp.push(self.sales.get(&keys.get(i).unwrap()).unwrap().into_inner());
         }
         tmp
     }
@@ -31,7 +31,7 @@ impl Contract {
     ) -> Vec<Option<Sale>> {
         let mut tmp = vec![];
         for sale_name in sales_names {
-            tmp.push(self.sales.get(&sale_name));
+            tmp.push(self.sales.get(&sale_name).map(VersionedSale::into_inner));
         }
         tmp
     }
@@ -65,7 +65,7 @@ impl Contract {
         let start = u64::from(from_index);
         let end = min(start + limit, sales.len());
         for i in start..end {
-            tmp.push(self.sales.get(&keys.get(i).unwrap()).unwrap());
+            tmp.push(self.sales.get(&keys.get(i).unwrap()).unwrap().into_inner());
         }
         tmp
     }
@@ -99,7 +99,7 @@ impl Contract {
         let start = u64::from(from_index);
         let end = min(start + limit, sales.len());
         for i in start..end {
-            tmp.push(self.sales.get(&keys.get(i).unwrap()).unwrap());
+            tmp.push(self.sales.get(&keys.get(i).unwrap()).unwrap().into_inner());
         }
         tmp
     }
@@ -133,13 +133,13 @@ impl Contract {
         let start = u64::from(from_index);
         let end = min(start + limit, sales.len());
         for i in start..end {
-            tmp.push(self.sales.get(&keys.get(i).unwrap()).unwrap());
+            tmp.push(self.sales.get(&keys.get(i).unwrap()).unwrap().into_inner());
         }
         tmp
     }
 
     pub fn get_sale(&self, nft_contract_token: ContractAndTokenId) -> Option<Sale> {
-        self.sales.get(&nft_contract_token)
+        self.sales.get(&nft_contract_token).map(VersionedSale::into_inner)
     }
     
 }
@@ -1,4 +1,5 @@
 const fs = require('fs');
+const crypto = require('crypto');
 const assert = require('assert');
 const testUtils = require('./test-utils');
 const nearAPI = require('near-api-js');
@@ -28,6 +29,7 @@ describe('NFT Series', function () {
 	const now = Date.now().toString();
 	let token_series_title = 'dog-' + now;
 	let token_id;
+	const lazy_series_title = 'cat-' + now;
 
 	/// users
 	const aliceId = 'alice-' + now + '.' + contractId;
@@ -295,4 +297,1194 @@ describe('NFT Series', function () {
 		console.log(owner_id);
 		assert.strictEqual(owner_id, contractId);
 	});
+
+	it('should allow the owner to list a series on the market for lazy minting', async function () {
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_create_series',
+			args: {
+				metadata: {
+					title: lazy_series_title,
+					media: 'https://placekitten.com/500',
+					copies: COPIES_TO_MINT,
+				},
+				royalty: {
+					[bobId]: 1000,
+				}
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_series_approve',
+			args: {
+				token_series_title: lazy_series_title,
+				account_id: marketId,
+				msg: JSON.stringify({
+					sale_conditions: {
+						near: parseNearAmount('1')
+					},
+					token_type: lazy_series_title,
+				})
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.01')
+		});
+
+		const sale = await contractAccount.viewFunction(
+			marketId,
+			'get_sale',
+			{ nft_contract_token: contractId + CONTRACT_TOKEN_DELIMETER + lazy_series_title }
+		);
+		assert.strictEqual(sale.owner_id, contractId);
+		assert.strictEqual(sale.is_series, true);
+	});
+
+	it('should allow someone to buy a lazy minted edition from the series sale', async function () {
+		await bob.functionCall({
+			contractId: marketId,
+			methodName: 'offer',
+			args: {
+				nft_contract_id: contractId,
+				token_id: lazy_series_title,
+				msg: JSON.stringify({ lazy_mint: true }),
+			},
+			gas,
+			attachedDeposit: parseNearAmount('1.1')
+		});
+
+		const tokens = await contractAccount.viewFunction(
+			contractId,
+			'nft_tokens_by_series',
+			{
+				token_series_title: lazy_series_title
+			}
+		);
+		assert.strictEqual(tokens.length, 1);
+		assert.strictEqual(tokens[0].owner_id, bobId);
+	});
+
+	it('should overlay per edition metadata onto the series metadata', async function () {
+		const token = await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_mint_series',
+			args: {
+				token_series_title: lazy_series_title,
+				receiver_id: contractId,
+				edition_metadata: {
+					media: 'https://placekitten.com/400',
+					extra: JSON.stringify({ variant: 'one of one' }),
+				}
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+		const { token_id } = JSON.parse(Buffer.from(token.status.SuccessValue, 'base64'));
+
+		const { metadata } = await contractAccount.viewFunction(
+			contractId,
+			'nft_token',
+			{ token_id }
+		);
+		assert.strictEqual(metadata.media, 'https://placekitten.com/400');
+		assert.strictEqual(metadata.title.indexOf(lazy_series_title), 0);
+		assert.ok(metadata.issued_at);
+	});
+
+	it('should allow the owner to update and then freeze the series metadata', async function () {
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_update_series_metadata',
+			args: {
+				token_series_title: lazy_series_title,
+				metadata: {
+					description: 'a cat',
+				}
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.01')
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_freeze_series_metadata',
+			args: {
+				token_series_title: lazy_series_title,
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+
+		const token_series = await contractAccount.viewFunction(
+			contractId,
+			'nft_get_series_json',
+			{ token_series_title: lazy_series_title }
+		);
+		assert.strictEqual(token_series.metadata.description, 'a cat');
+		assert.strictEqual(token_series.metadata_frozen, true);
+
+		try {
+			await contractAccount.functionCall({
+				contractId,
+				methodName: 'nft_update_series_metadata',
+				args: {
+					token_series_title: lazy_series_title,
+					metadata: {
+						description: 'a dog',
+					}
+				},
+				gas,
+				attachedDeposit: parseNearAmount('0.01')
+			});
+			assert(false);
+		} catch(e) {
+			assert(true);
+		}
+	});
+
+	it('should allow the owner to hand the series to another account', async function () {
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_transfer_series_ownership',
+			args: {
+				token_series_title,
+				new_owner_id: aliceId,
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+		await alice.functionCall({
+			contractId,
+			methodName: 'nft_accept_series_ownership',
+			args: {
+				token_series_title,
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.01')
+		});
+
+		const token_series = await contractAccount.viewFunction(
+			contractId,
+			'nft_get_series_json',
+			{ token_series_title }
+		);
+		assert.strictEqual(token_series.owner_id, aliceId);
+	});
+
+	it('should NOT allow a series with invalid royalties', async function () {
+		const royalties = [
+			{ [bobId]: 10001 },
+			{ [bobId]: 0 },
+		];
+		for (const royalty of royalties) {
+			try {
+				await contractAccount.functionCall({
+					contractId,
+					methodName: 'nft_create_series',
+					args: {
+						metadata: {
+							title: 'bad-royalty-' + Date.now(),
+						},
+						royalty,
+					},
+					gas,
+					attachedDeposit: parseNearAmount('0.1')
+				});
+				assert(false);
+			} catch(e) {
+				assert(/royalty/.test(e.toString()));
+			}
+		}
+	});
+
+	it('should show the payout for a token without transferring it', async function () {
+		const tokens = await contractAccount.viewFunction(
+			contractId,
+			'nft_tokens_by_series',
+			{ token_series_title: lazy_series_title }
+		);
+		const { token_id } = tokens.find(({ owner_id }) => owner_id === contractId);
+
+		const { payout } = await contractAccount.viewFunction(
+			contractId,
+			'nft_payout',
+			{
+				token_id,
+				balance: parseNearAmount('1'),
+				max_len_payout: 10,
+			}
+		);
+		assert.strictEqual(payout[bobId], parseNearAmount('0.1'));
+		assert.strictEqual(payout[contractId], parseNearAmount('0.9'));
+	});
+
+	it('should pay out exactly the balance at odd prices', async function () {
+		const tokens = await contractAccount.viewFunction(
+			contractId,
+			'nft_tokens_by_series',
+			{ token_series_title: lazy_series_title }
+		);
+		const { token_id } = tokens.find(({ owner_id }) => owner_id === contractId);
+		const balance = '1000000000000000000009999';
+
+		const { payout } = await contractAccount.viewFunction(
+			contractId,
+			'nft_payout',
+			{
+				token_id,
+				balance,
+				max_len_payout: 10,
+			}
+		);
+		const total = Object.values(payout).reduce((a, c) => a.add(new BN(c)), new BN('0'));
+		assert.strictEqual(total.toString(), balance);
+		assert.strictEqual(payout[bobId], '100000000000000000000999');
+	});
+
+	it('should allow the owner to batch mint editions to many receivers', async function () {
+		const batch_series_title = 'batch-' + now;
+		await alice.functionCall({
+			contractId,
+			methodName: 'nft_create_series',
+			args: {
+				metadata: {
+					title: batch_series_title,
+					copies: 10,
+				},
+				royalty: {}
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+
+		const aliceBalanceBefore = (await getAccountBalance(aliceId)).total;
+		const contractBalanceBefore = (await getAccountBalance(contractId)).total;
+		const res = await alice.functionCall({
+			contractId,
+			methodName: 'nft_batch_mint_series',
+			args: {
+				token_series_title: batch_series_title,
+				receivers: [[aliceId, 2], [bobId, 3]],
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.5')
+		});
+		const minted = JSON.parse(Buffer.from(res.status.SuccessValue, 'base64'));
+		assert.strictEqual(minted, 5);
+
+		// storage is charged once for the batch, alice pays for it and the contract is not out of pocket
+		const aliceBalanceAfter = (await getAccountBalance(aliceId)).total;
+		const contractBalanceAfter = (await getAccountBalance(contractId)).total;
+		assert(new BN(aliceBalanceAfter).lt(new BN(aliceBalanceBefore)));
+		assert(new BN(contractBalanceAfter).gte(new BN(contractBalanceBefore)));
+
+		const supply_for_series = await contractAccount.viewFunction(
+			contractId,
+			'nft_supply_for_series',
+			{ token_series_title: batch_series_title }
+		);
+		assert.strictEqual(parseInt(supply_for_series, 10), 5);
+	});
+
+	it('should allow the token owner to burn and not reuse the edition', async function () {
+		const batch_series_title = 'batch-' + now;
+		const { token_series_id } = await contractAccount.viewFunction(
+			contractId,
+			'nft_get_series_json',
+			{ token_series_title: batch_series_title }
+		);
+		const tokens = await contractAccount.viewFunction(
+			contractId,
+			'nft_tokens_by_series',
+			{ token_series_title: batch_series_title }
+		);
+		const { token_id: burned_token_id } = tokens.find(({ owner_id }) => owner_id === aliceId);
+
+		await alice.functionCall({
+			contractId,
+			methodName: 'nft_burn',
+			args: {
+				token_id: burned_token_id,
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+
+		const token = await contractAccount.viewFunction(
+			contractId,
+			'nft_token',
+			{ token_id: burned_token_id }
+		);
+		assert.strictEqual(token, null);
+
+		// 5 editions were minted, the next one is 6 even though the supply is down to 4
+		const res = await alice.functionCall({
+			contractId,
+			methodName: 'nft_mint_series',
+			args: {
+				token_series_title: batch_series_title,
+				receiver_id: aliceId,
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+		const { token_id: minted_token_id } = JSON.parse(Buffer.from(res.status.SuccessValue, 'base64'));
+		assert.strictEqual(minted_token_id, token_series_id + TOKEN_DELIMETER + '6');
+	});
+
+	it('should allow a granted minter to mint up to their quota', async function () {
+		const minter_series_title = 'minter-' + now;
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_create_series',
+			args: {
+				metadata: {
+					title: minter_series_title,
+				},
+				royalty: {}
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_grant_series_minter',
+			args: {
+				token_series_title: minter_series_title,
+				account_id: aliceId,
+				quota: 1,
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.01')
+		});
+
+		const minters = await contractAccount.viewFunction(
+			contractId,
+			'nft_series_minters',
+			{ token_series_title: minter_series_title }
+		);
+		assert.strictEqual(minters[aliceId].quota, 1);
+
+		await alice.functionCall({
+			contractId,
+			methodName: 'nft_mint_series',
+			args: {
+				token_series_title: minter_series_title,
+				receiver_id: aliceId
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+
+		try {
+			await alice.functionCall({
+				contractId,
+				methodName: 'nft_mint_series',
+				args: {
+					token_series_title: minter_series_title,
+					receiver_id: aliceId
+				},
+				gas,
+				attachedDeposit: parseNearAmount('0.1')
+			});
+			assert(false);
+		} catch(e) {
+			assert(/quota/.test(e.toString()));
+		}
+	});
+
+	it('should allow anyone to buy an edition directly from the series contract', async function () {
+		const public_series_title = 'public-' + now;
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_create_series',
+			args: {
+				metadata: {
+					title: public_series_title,
+				},
+				royalty: {}
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_set_series_public_mint',
+			args: {
+				token_series_title: public_series_title,
+				public_mint: {
+					price: parseNearAmount('1'),
+					ft_prices: {},
+					split: {
+						[bobId]: 2000,
+					},
+				},
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.01')
+		});
+
+		const bobBalanceBefore = (await getAccountBalance(bobId)).total;
+		const aliceBalanceBefore = (await getAccountBalance(aliceId)).total;
+		await alice.functionCall({
+			contractId,
+			methodName: 'nft_public_mint',
+			args: {
+				token_series_title: public_series_title,
+			},
+			gas,
+			attachedDeposit: parseNearAmount('1.1')
+		});
+		const bobBalanceAfter = (await getAccountBalance(bobId)).total;
+		assert.strictEqual(new BN(bobBalanceAfter).sub(new BN(bobBalanceBefore)).toString(), parseNearAmount('0.2'));
+		// alice paid the price and storage, and got the rest of the deposit back
+		const aliceSpent = new BN(aliceBalanceBefore).sub(new BN((await getAccountBalance(aliceId)).total));
+		assert(aliceSpent.gt(new BN(parseNearAmount('1'))));
+		assert(aliceSpent.lt(new BN(parseNearAmount('1.1'))));
+
+		const tokens = await contractAccount.viewFunction(
+			contractId,
+			'nft_tokens_by_series',
+			{ token_series_title: public_series_title }
+		);
+		assert.strictEqual(tokens[0].owner_id, aliceId);
+	});
+
+	it('should allow anyone to buy an edition with a fungible token', async function () {
+		const ft_series_title = 'public-ft-' + now;
+		// bob stands in for the FT contract, it has no code so the proceeds can't be transferred
+		const ftTokenId = bobId;
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'add_ft_token_ids',
+			args: {
+				ft_token_ids: [ftTokenId],
+			},
+			gas,
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_create_series',
+			args: {
+				metadata: {
+					title: ft_series_title,
+				},
+				royalty: {}
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_set_series_public_mint',
+			args: {
+				token_series_title: ft_series_title,
+				public_mint: {
+					ft_prices: {
+						[ftTokenId]: '100',
+					},
+				},
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.01')
+		});
+		// FT mints take storage from the buyer's storage balance
+		await alice.functionCall({
+			contractId,
+			methodName: 'storage_deposit',
+			args: {},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+
+		const res = await bob.functionCall({
+			contractId,
+			methodName: 'ft_on_transfer',
+			args: {
+				sender_id: aliceId,
+				amount: '150',
+				msg: JSON.stringify({ token_series_title: ft_series_title }),
+			},
+			gas,
+		});
+		assert.strictEqual(JSON.parse(Buffer.from(res.status.SuccessValue, 'base64')), '50');
+
+		const tokens = await contractAccount.viewFunction(
+			contractId,
+			'nft_tokens_by_series',
+			{ token_series_title: ft_series_title }
+		);
+		assert.strictEqual(tokens[0].owner_id, aliceId);
+
+		// the failed ft_transfer is kept for ft_withdraw_payout
+		const owed = await contractAccount.viewFunction(contractId, 'get_ft_payouts_owed', { account_id: contractId });
+		assert.strictEqual(owed[ftTokenId], '100');
+	});
+
+	it('should cap the supply once the mint window has ended', async function () {
+		const timed_series_title = 'timed-' + now;
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_create_series',
+			args: {
+				metadata: {
+					title: timed_series_title,
+				},
+				royalty: {}
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_mint_series',
+			args: {
+				token_series_title: timed_series_title,
+				receiver_id: contractId
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_set_series_mint_window',
+			args: {
+				token_series_title: timed_series_title,
+				mint_ends_at: new BN(Date.now()).mul(new BN('1000000')).toString(),
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.01')
+		});
+
+		try {
+			await contractAccount.functionCall({
+				contractId,
+				methodName: 'nft_mint_series',
+				args: {
+					token_series_title: timed_series_title,
+					receiver_id: contractId
+				},
+				gas,
+				attachedDeposit: parseNearAmount('0.1')
+			});
+			assert(false);
+		} catch(e) {
+			assert(/minting ended/.test(e.toString()));
+		}
+
+		const token_series = await contractAccount.viewFunction(
+			contractId,
+			'nft_get_series_json',
+			{ token_series_title: timed_series_title }
+		);
+		assert.strictEqual(token_series.metadata.copies, 1);
+	});
+
+	it('should limit the editions each account can get', async function () {
+		const limited_series_title = 'limited-' + now;
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_create_series',
+			args: {
+				metadata: {
+					title: limited_series_title,
+				},
+				royalty: {}
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_set_series_max_per_account',
+			args: {
+				token_series_title: limited_series_title,
+				max_per_account: 1,
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.01')
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_mint_series',
+			args: {
+				token_series_title: limited_series_title,
+				receiver_id: aliceId
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+
+		const mints_left = await contractAccount.viewFunction(
+			contractId,
+			'nft_series_mints_left',
+			{ token_series_title: limited_series_title, account_id: aliceId }
+		);
+		assert.strictEqual(mints_left, 0);
+
+		try {
+			await contractAccount.functionCall({
+				contractId,
+				methodName: 'nft_mint_series',
+				args: {
+					token_series_title: limited_series_title,
+					receiver_id: aliceId
+				},
+				gas,
+				attachedDeposit: parseNearAmount('0.1')
+			});
+			assert(false);
+		} catch(e) {
+			assert(/max_per_account/.test(e.toString()));
+		}
+	});
+
+	it('should only allow allowlisted accounts to mint during the allowlist phase', async function () {
+		const allowlist_series_title = 'allowlist-' + now;
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_create_series',
+			args: {
+				metadata: {
+					title: allowlist_series_title,
+				},
+				royalty: {}
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_set_series_public_mint',
+			args: {
+				token_series_title: allowlist_series_title,
+				public_mint: {
+					price: parseNearAmount('0.5'),
+					ft_prices: {},
+				},
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.01')
+		});
+		// single leaf tree, the root is the leaf and the proof is empty
+		const root = crypto.createHash('sha256').update(aliceId).digest('base64');
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_set_series_allowlist',
+			args: {
+				token_series_title: allowlist_series_title,
+				root,
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.01')
+		});
+
+		try {
+			await bob.functionCall({
+				contractId,
+				methodName: 'nft_public_mint',
+				args: {
+					token_series_title: allowlist_series_title,
+				},
+				gas,
+				attachedDeposit: parseNearAmount('0.6')
+			});
+			assert(false);
+		} catch(e) {
+			assert(/allowlist only/.test(e.toString()));
+		}
+
+		await alice.functionCall({
+			contractId,
+			methodName: 'nft_allowlist_mint',
+			args: {
+				token_series_title: allowlist_series_title,
+				proof: [],
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.6')
+		});
+		const tokens = await contractAccount.viewFunction(
+			contractId,
+			'nft_tokens_by_series',
+			{ token_series_title: allowlist_series_title }
+		);
+		assert.strictEqual(tokens[0].owner_id, aliceId);
+	});
+
+	it('should allow the owner to delete a series with nothing minted', async function () {
+		const typo_series_title = 'tpyo-' + now;
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_create_series',
+			args: {
+				metadata: {
+					title: typo_series_title,
+				},
+				royalty: {}
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_delete_series',
+			args: {
+				token_series_title: typo_series_title,
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+
+		try {
+			await contractAccount.viewFunction(
+				contractId,
+				'nft_get_series_json',
+				{ token_series_title: typo_series_title }
+			);
+			assert(false);
+		} catch(e) {
+			assert(/no series/.test(e.toString()));
+		}
+	});
+
+	it('should address a series by the id in its token ids', async function () {
+		const token_series_id = token_id.split(TOKEN_DELIMETER)[0];
+
+		const token_series = await contractAccount.viewFunction(
+			contractId,
+			'nft_get_series_json_by_id',
+			{ token_series_id }
+		);
+		assert.strictEqual(token_series.token_series_id, token_series_id);
+		assert.strictEqual(token_series.metadata.title, token_series_title);
+
+		const tokens = await contractAccount.viewFunction(
+			contractId,
+			'nft_tokens_by_series_id',
+			{ token_series_id }
+		);
+		assert.ok(tokens.some((token) => token.token_id === token_id));
+	});
+
+	it('should list the series an account owns', async function () {
+		const supply = await contractAccount.viewFunction(
+			contractId,
+			'nft_series_supply_for_owner',
+			{ account_id: aliceId }
+		);
+		assert.strictEqual(parseInt(supply, 10), 1);
+
+		const series = await contractAccount.viewFunction(
+			contractId,
+			'nft_series_for_owner',
+			{ account_id: aliceId }
+		);
+		assert.strictEqual(series[0].metadata.title, token_series_title);
+	});
+
+	it('should only allow series creators to create series in curated mode', async function () {
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'set_series_creators_only',
+			args: {
+				series_creators_only: true,
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'grant_role',
+			args: {
+				account_id: aliceId,
+				role: 'series_creator',
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+
+		try {
+			await bob.functionCall({
+				contractId,
+				methodName: 'nft_create_series',
+				args: {
+					metadata: {
+						title: 'curated-bob-' + now,
+					},
+					royalty: {}
+				},
+				gas,
+				attachedDeposit: parseNearAmount('0.1')
+			});
+			assert(false);
+		} catch(e) {
+			assert(/not series creator/.test(e.toString()));
+		}
+
+		await alice.functionCall({
+			contractId,
+			methodName: 'nft_create_series',
+			args: {
+				metadata: {
+					title: 'curated-alice-' + now,
+				},
+				royalty: {}
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'set_series_creators_only',
+			args: {
+				series_creators_only: false,
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+	});
+
+	it('should pause transfers without pausing minting', async function () {
+		const tokens = await contractAccount.viewFunction(
+			contractId,
+			'nft_tokens_by_series',
+			{ token_series_title: lazy_series_title }
+		);
+		const { token_id } = tokens.find(({ owner_id }) => owner_id === contractId);
+
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'pause',
+			args: {
+				feature: 'transfer',
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+
+		const paused = await contractAccount.viewFunction(contractId, 'get_paused', {});
+		assert.deepStrictEqual(paused, { mint: false, transfer: true, payout: false });
+
+		try {
+			await contractAccount.functionCall({
+				contractId,
+				methodName: 'nft_transfer',
+				args: {
+					receiver_id: aliceId,
+					token_id,
+				},
+				gas,
+				attachedDeposit: '1'
+			});
+			assert(false);
+		} catch(e) {
+			assert(/transfers paused/.test(e.toString()));
+		}
+
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_mint_series',
+			args: {
+				token_series_title: lazy_series_title,
+				receiver_id: contractId,
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'unpause',
+			args: {
+				feature: 'transfer',
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+	});
+
+	it('should stage an upgrade and hold it for the timelock', async function () {
+		const contractBytes = fs.readFileSync('./out/main.wasm');
+		const code_hash = nearAPI.utils.serialize.base_encode(crypto.createHash('sha256').update(contractBytes).digest());
+
+		// no timelock yet, the state is current so migrate only clears the staged upgrade
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'stage_upgrade',
+			args: {
+				code: contractBytes.toString('base64'),
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'deploy_upgrade',
+			args: {},
+			gas,
+			attachedDeposit: '1'
+		});
+		assert.strictEqual(await contractAccount.viewFunction(contractId, 'get_staged_upgrade', {}), null);
+
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'set_upgrade_timelock',
+			args: {
+				upgrade_timelock: (3600 * 1e9).toString(),
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'stage_upgrade',
+			args: {
+				code: contractBytes.toString('base64'),
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+
+		const staged_upgrade = await contractAccount.viewFunction(contractId, 'get_staged_upgrade', {});
+		assert.strictEqual(staged_upgrade.code_hash, code_hash);
+
+		try {
+			await contractAccount.functionCall({
+				contractId,
+				methodName: 'deploy_upgrade',
+				args: {},
+				gas,
+				attachedDeposit: '1'
+			});
+			assert(false);
+		} catch(e) {
+			assert(/upgrade timelocked/.test(e.toString()));
+		}
+
+		// clearing the timelock waits out the current one
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'set_upgrade_timelock',
+			args: {},
+			gas,
+			attachedDeposit: '1'
+		});
+		assert.strictEqual(await contractAccount.viewFunction(contractId, 'get_upgrade_timelock', {}), (3600 * 1e9).toString());
+		const pending = await contractAccount.viewFunction(contractId, 'get_pending_upgrade_timelock', {});
+		assert.strictEqual(pending.upgrade_timelock, null);
+
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'stage_upgrade',
+			args: {
+				code: contractBytes.toString('base64'),
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+		try {
+			await contractAccount.functionCall({
+				contractId,
+				methodName: 'deploy_upgrade',
+				args: {},
+				gas,
+				attachedDeposit: '1'
+			});
+			assert(false);
+		} catch(e) {
+			assert(/upgrade timelocked/.test(e.toString()));
+		}
+	});
+
+	it('should draw series storage from a prepaid storage balance', async function () {
+		await alice.functionCall({
+			contractId,
+			methodName: 'storage_deposit',
+			args: {},
+			gas,
+			attachedDeposit: parseNearAmount('0.5')
+		});
+		const before = await contractAccount.viewFunction(contractId, 'storage_balance_of', { account_id: aliceId });
+
+		await alice.functionCall({
+			contractId,
+			methodName: 'nft_create_series',
+			args: {
+				metadata: {
+					title: 'prepaid-' + now,
+				},
+				royalty: {}
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+
+		const after = await contractAccount.viewFunction(contractId, 'storage_balance_of', { account_id: aliceId });
+		assert(new BN(after.available).lt(new BN(before.available)));
+
+		// mints draw from the storage balance too
+		await alice.functionCall({
+			contractId,
+			methodName: 'nft_create_series',
+			args: {
+				metadata: {
+					title: 'prepaid-mint-' + now,
+				},
+				royalty: {}
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+		const before_mint = await contractAccount.viewFunction(contractId, 'storage_balance_of', { account_id: aliceId });
+		await alice.functionCall({
+			contractId,
+			methodName: 'nft_mint_series',
+			args: {
+				token_series_title: 'prepaid-mint-' + now,
+				receiver_id: aliceId,
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+		const after_mint = await contractAccount.viewFunction(contractId, 'storage_balance_of', { account_id: aliceId });
+		assert(new BN(after_mint.available).lt(new BN(before_mint.available)));
+		const [{ owner_id }] = await contractAccount.viewFunction(
+			contractId,
+			'nft_tokens_by_series',
+			{ token_series_title: 'prepaid-mint-' + now }
+		);
+		assert.strictEqual(owner_id, aliceId);
+
+		await alice.functionCall({
+			contractId,
+			methodName: 'storage_withdraw',
+			args: {},
+			gas,
+			attachedDeposit: '1'
+		});
+		const withdrawn = await contractAccount.viewFunction(contractId, 'storage_balance_of', { account_id: aliceId });
+		assert.strictEqual(withdrawn.available, '0');
+
+		// freed storage goes back to the storage balance
+		await alice.functionCall({
+			contractId,
+			methodName: 'nft_delete_series',
+			args: {
+				token_series_title: 'prepaid-' + now,
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+		const refunded = await contractAccount.viewFunction(contractId, 'storage_balance_of', { account_id: aliceId });
+		assert(new BN(refunded.available).gt(new BN('0')));
+
+		// alice still owns tokens from the earlier tests
+		try {
+			await alice.functionCall({
+				contractId,
+				methodName: 'storage_unregister',
+				args: {},
+				gas,
+				attachedDeposit: '1'
+			});
+			assert(false);
+		} catch(e) {
+			assert(/use force/.test(e.toString()));
+		}
+	});
+
+	it('should mint and burn but not transfer soulbound series tokens', async function () {
+		const badge_series_title = 'badge-' + now;
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_create_series',
+			args: {
+				metadata: {
+					title: badge_series_title,
+				},
+				royalty: {},
+				transferable: false,
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+		await contractAccount.functionCall({
+			contractId,
+			methodName: 'nft_mint_series',
+			args: {
+				token_series_title: badge_series_title,
+				receiver_id: aliceId,
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.1')
+		});
+
+		const token_series = await contractAccount.viewFunction(
+			contractId,
+			'nft_get_series_json',
+			{ token_series_title: badge_series_title }
+		);
+		assert.strictEqual(token_series.transferable, false);
+
+		const [{ token_id }] = await contractAccount.viewFunction(
+			contractId,
+			'nft_tokens_by_series',
+			{ token_series_title: badge_series_title }
+		);
+		try {
+			await alice.functionCall({
+				contractId,
+				methodName: 'nft_transfer',
+				args: {
+					receiver_id: bobId,
+					token_id,
+				},
+				gas,
+				attachedDeposit: '1'
+			});
+			assert(false);
+		} catch(e) {
+			assert(/series is not transferable/.test(e.toString()));
+		}
+
+		await alice.functionCall({
+			contractId,
+			methodName: 'nft_burn',
+			args: {
+				token_id,
+			},
+			gas,
+			attachedDeposit: '1'
+		});
+	});
+
+	it('should log typed events on contract metadata updates', async () => {
+		const { receipts_outcome } = await contractAccount.functionCall({
+			contractId,
+			methodName: 'set_contract_metadata',
+			args: {
+				metadata: {
+					spec: 'nft-1.0.0',
+					name: 'NFT Series',
+					symbol: 'NFT',
+				},
+			},
+			gas,
+			attachedDeposit: parseNearAmount('0.01')
+		});
+
+		const events = receipts_outcome
+			.flatMap(({ outcome }) => outcome.logs)
+			.filter((log) => log.startsWith('EVENT_JSON:'))
+			.map((log) => JSON.parse(log.substring('EVENT_JSON:'.length)));
+		const event = events.find(({ event }) => event === 'contract_metadata_update');
+		assert.strictEqual(event.standard, 'nep171');
+		assert.strictEqual(event.version, '1.1.0');
+
+		const { name } = await contractAccount.viewFunction(contractId, 'nft_metadata');
+		assert.strictEqual(name, 'NFT Series');
+	});
 });